@@ -141,7 +141,7 @@ fn draw_canvas(application: &Application, buffer: &mut CellBuffer) -> Result<()>
 
 fn process_mouse_events(mouse: MouseEvent, buffer: &mut CellBuffer) {
     if mouse.event_type == MouseEventType::MouseMove
-        || mouse.event_type == MouseEventType::Click
+        || mouse.event_type == MouseEventType::MouseDown
         || mouse.event_type == MouseEventType::DoubleClick
     {
         if mouse.left_button {
@@ -165,7 +165,9 @@ fn process_mouse_events(mouse: MouseEvent, buffer: &mut CellBuffer) {
         }
     }
 
-    if mouse.event_type == MouseEventType::Wheel {
+    if mouse.event_type == MouseEventType::Wheel
+        || mouse.event_type == MouseEventType::HorizontalWheel
+    {
         buffer.write_str(
             &format!("{}", mouse.wheel_delta),
             Point2d::new(0, 2),
@@ -10,6 +10,15 @@ extern crate tokterm_termion;
 #[cfg(all(unix, feature = "ncurses"))]
 extern crate tokterm_ncurses;
 
+#[cfg(all(unix, any(feature = "ansi", feature = "session")))]
+extern crate tokterm_ansi;
+
+#[cfg(feature = "telnet")]
+extern crate tokterm_telnet;
+
+#[cfg(all(unix, feature = "session"))]
+extern crate tokterm_session;
+
 mod application;
 use application::execute;
 
@@ -41,3 +50,38 @@ fn launch() -> Result<()> {
     execute(&mut application)?;
     Ok(())
 }
+
+#[cfg(all(unix, feature = "ansi"))]
+fn launch() -> Result<()> {
+    use tokterm_ansi::application::AnsiApplication;
+    let mut application = AnsiApplication::create()?;
+    execute(&mut application)?;
+    Ok(())
+}
+
+#[cfg(feature = "telnet")]
+fn launch() -> Result<()> {
+    use tokterm_telnet::server::TelnetServer;
+    let server = TelnetServer::bind("127.0.0.1:2323")?;
+    server.serve(|application| execute(application))?;
+    Ok(())
+}
+
+#[cfg(all(unix, feature = "session"))]
+fn launch() -> Result<()> {
+    use tokterm_ansi::application::AnsiApplication;
+    use tokterm_session::client::SessionClient;
+    use tokterm_session::server::SessionApplication;
+    let path = "/tmp/tokterm.sock";
+
+    // `tokterm attach` connects to the session started by `tokterm`.
+    if std::env::args().nth(1).as_ref().map(String::as_str) == Some("attach") {
+        let mut application = AnsiApplication::create()?;
+        SessionClient::attach(path)?.run(&mut application)?;
+    } else {
+        let mut application = SessionApplication::bind(path)?;
+        execute(&mut application)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,250 @@
+use std::io::{self, stdin, Read, Stdout, Write};
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::thread;
+use terminal::AnsiTerminal;
+use tokterm_core::drawing::point_2d::Point2d;
+use tokterm_core::drawing::size_2d::Size2d;
+use tokterm_core::events::event::{Event, WindowEvent, WindowEventType};
+use tokterm_core::events::event_queue::EventQueue;
+use tokterm_core::input::keyboard_state::KeyboardState;
+use tokterm_core::input::kitty::{ENABLE_KITTY_KEYBOARD, KITTY_KEYBOARD_QUERY};
+use tokterm_core::input::mouse_state::MouseState;
+use tokterm_core::input::parser::InputParser;
+use tokterm_core::input::sgr_mouse::ENABLE_SGR_MOUSE;
+use tokterm_core::system::application::Application;
+use tokterm_core::system::capabilities::MouseSupport;
+use tokterm_core::system::focus::ENABLE_FOCUS_EVENTS;
+use tokterm_core::system::paste::ENABLE_BRACKETED_PASTE;
+use tokterm_core::system::signals::{
+    suspend_process, take_resume, take_suspend, take_window_resize,
+};
+use tokterm_core::system::terminal::Terminal;
+use tokterm_core::Error;
+use tokterm_core::Result;
+
+pub struct AnsiApplication<W: Write> {
+    terminal: AnsiTerminal<W>,
+    input: Receiver<io::Result<Vec<u8>>>,
+    parser: InputParser,
+    event_queue: EventQueue,
+    mouse_state: MouseState,
+    keyboard_state: KeyboardState,
+    size: Size2d,
+}
+
+impl AnsiApplication<Stdout> {
+    /// Creates an application over the standard input and output.
+    pub fn create() -> Result<AnsiApplication<Stdout>> {
+        AnsiApplication::new(stdin(), AnsiTerminal::create()?)
+    }
+}
+
+impl<W: Write> AnsiApplication<W> {
+    /// Creates an application reading its input from any reader, like the
+    /// master side of a PTY or a socket. The reader is moved to a thread that
+    /// outlives the application until its read returns, see `spawn_reader`.
+    pub fn new<R: Read + Send + 'static>(
+        input: R,
+        mut terminal: AnsiTerminal<W>,
+    ) -> Result<AnsiApplication<W>> {
+        if terminal.capabilities().alternate_screen {
+            terminal.set_alternate_screen(true)?;
+        }
+
+        enable_reports(&mut terminal)?;
+        let size = terminal.get_console_size()?;
+
+        Ok(AnsiApplication {
+            terminal,
+            input: spawn_reader(input),
+            parser: InputParser::new(),
+            event_queue: EventQueue::new(),
+            mouse_state: MouseState::new(),
+            keyboard_state: KeyboardState::new(),
+            size,
+        })
+    }
+
+    #[inline]
+    pub fn get_ansi_terminal(&mut self) -> &mut AnsiTerminal<W> {
+        &mut self.terminal
+    }
+
+    /// Reports the new console size once the window was resized, skipping
+    /// resizes that left the size as it was.
+    fn process_window_resize(&mut self) -> Result<()> {
+        let size = self.terminal.get_console_size()?;
+
+        if size != self.size {
+            self.size = size;
+            self.event_queue.add_event(Event::Window(WindowEvent {
+                event_type: WindowEventType::WindowResize,
+                position: Point2d::empty(),
+                size,
+            }));
+        }
+
+        Ok(())
+    }
+
+    /// Takes the terminal again after being continued, reporting it with a
+    /// `WindowResume` event along with any resize missed meanwhile.
+    fn resume(&mut self) -> Result<()> {
+        self.terminal.resume()?;
+        enable_reports(&mut self.terminal)?;
+        self.process_window_resize()?;
+        self.event_queue.add_event(Event::Window(WindowEvent {
+            event_type: WindowEventType::WindowResume,
+            position: Point2d::empty(),
+            size: self.size,
+        }));
+
+        Ok(())
+    }
+
+    fn process_events(&mut self, events: Vec<Event>) {
+        for mut event in events {
+            match event {
+                Event::Mouse(mouse) => self.mouse_state.update_from_event(mouse),
+                Event::Keyboard(keyboard) => self.keyboard_state.update_from_event(keyboard),
+                // the focus reports don't tell the size, unlike native events.
+                Event::Window(ref mut window) => window.size = self.size,
+                _ => (),
+            }
+
+            self.event_queue.add_event(event);
+        }
+    }
+}
+
+impl<W: Write> Application for AnsiApplication<W> {
+    #[inline]
+    fn get_terminal(&self) -> &Terminal {
+        &self.terminal
+    }
+
+    #[inline]
+    fn get_mut_terminal(&mut self) -> &mut Terminal {
+        &mut self.terminal
+    }
+
+    #[inline]
+    fn get_mouse_state(&self) -> &MouseState {
+        &self.mouse_state
+    }
+
+    #[inline]
+    fn get_keyboard_state(&self) -> &KeyboardState {
+        &self.keyboard_state
+    }
+
+    #[inline]
+    fn get_event_queue(&self) -> &EventQueue {
+        &self.event_queue
+    }
+
+    #[inline]
+    fn get_mut_event_queue(&mut self) -> &mut EventQueue {
+        &mut self.event_queue
+    }
+
+    fn listen_events(&mut self) -> Result<()> {
+        // only the standard output gets the signals of its window and job.
+        if self.terminal.get_fd().is_some() {
+            if take_suspend() {
+                self.terminal.suspend()?;
+                suspend_process()?;
+            }
+
+            if take_resume() {
+                self.resume()?;
+            }
+
+            if take_window_resize() {
+                self.process_window_resize()?;
+            }
+        }
+
+        loop {
+            let bytes = match self.input.try_recv() {
+                Ok(Ok(bytes)) => bytes,
+                Ok(Err(error)) => return Err(Error::io("Couldn't read the input.", error)),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => return Err(Error::TerminalClosed),
+            };
+
+            if bytes.is_empty() {
+                return Err(Error::TerminalClosed);
+            }
+
+            let events = self.parser.parse(&bytes);
+            self.process_events(events);
+        }
+
+        // an escape left alone for a while was the escape key.
+        let events = self.parser.check_timeout();
+        self.process_events(events);
+
+        Ok(())
+    }
+}
+
+/// Enables the mouse and focus reports, bracketed paste and the kitty
+/// keyboard protocol when the terminal supports them. Only SGR mouse reports
+/// are parsed, the legacy ones would turn into keys.
+fn enable_reports<W: Write>(terminal: &mut AnsiTerminal<W>) -> Result<()> {
+    let capabilities = terminal.capabilities();
+
+    if capabilities.mouse == MouseSupport::Sgr {
+        terminal.write_sequence(ENABLE_SGR_MOUSE)?;
+    }
+
+    if capabilities.focus_events {
+        terminal.write_sequence(ENABLE_FOCUS_EVENTS)?;
+    }
+
+    if capabilities.bracketed_paste {
+        terminal.write_sequence(ENABLE_BRACKETED_PASTE)?;
+    }
+
+    if capabilities.kitty_keyboard {
+        terminal.write_sequence(ENABLE_KITTY_KEYBOARD)?;
+        terminal.write_sequence(KITTY_KEYBOARD_QUERY)?;
+    }
+
+    Ok(())
+}
+
+/// Reads the input on its own thread, so any blocking reader can be polled.
+/// The end of the input is sent as an empty read.
+///
+/// A blocked read can't be interrupted, so once the receiver is dropped the
+/// thread lingers until the reader returns, ending right after. The standard
+/// input stays blocked until the next key, swallowing it, while sockets and
+/// PTYs should be shut down or closed by their owner.
+pub fn spawn_reader<R: Read + Send + 'static>(mut input: R) -> Receiver<io::Result<Vec<u8>>> {
+    let (sender, receiver) = channel();
+
+    thread::spawn(move || {
+        let mut buffer = [0; 1024];
+
+        loop {
+            let read = match input.read(&mut buffer) {
+                Ok(count) => Ok(buffer[..count].to_vec()),
+                Err(ref error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => Err(error),
+            };
+
+            let done = match read {
+                Ok(ref bytes) => bytes.is_empty(),
+                Err(_) => true,
+            };
+
+            if sender.send(read).is_err() || done {
+                return;
+            }
+        }
+    });
+
+    receiver
+}
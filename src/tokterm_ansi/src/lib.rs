@@ -0,0 +1,6 @@
+extern crate libc;
+extern crate tokterm_core;
+
+pub mod application;
+pub mod raw_mode;
+pub mod terminal;
@@ -0,0 +1,76 @@
+use libc::{cfmakeraw, tcgetattr, tcsetattr, termios, TCSANOW};
+use std::io;
+use std::mem::zeroed;
+use std::os::unix::io::RawFd;
+use tokterm_core::system::signals::enable_suspend_key;
+use tokterm_core::Error;
+use tokterm_core::Result;
+
+/// Puts a terminal in raw mode, restoring its original attributes on drop.
+/// The suspend key still sends `SIGTSTP`, so job control keeps working.
+pub struct RawMode {
+    fd: RawFd,
+    original: termios,
+    raw: termios,
+}
+
+impl RawMode {
+    pub fn enable(fd: RawFd) -> Result<RawMode> {
+        let mut original: termios = unsafe { zeroed() };
+
+        if unsafe { tcgetattr(fd, &mut original) } != 0 {
+            return Err(Error::io(
+                "Couldn't retrieve the terminal attributes.",
+                io::Error::last_os_error(),
+            ));
+        }
+
+        let mut raw = original;
+        unsafe { cfmakeraw(&mut raw) };
+        enable_suspend_key(&mut raw);
+
+        if unsafe { tcsetattr(fd, TCSANOW, &raw) } != 0 {
+            return Err(Error::io(
+                "Couldn't enter into raw mode.",
+                io::Error::last_os_error(),
+            ));
+        }
+
+        Ok(RawMode { fd, original, raw })
+    }
+
+    /// Restores the original attributes while the process is suspended.
+    #[inline]
+    pub fn suspend(&self) {
+        restore_attributes(self.fd, &self.original);
+    }
+
+    /// Enters raw mode again after being suspended.
+    #[inline]
+    pub fn resume(&self) {
+        restore_attributes(self.fd, &self.raw);
+    }
+
+    #[inline]
+    pub fn get_fd(&self) -> RawFd {
+        self.fd
+    }
+
+    /// Gets the attributes the terminal had before entering raw mode.
+    #[inline]
+    pub fn get_original(&self) -> termios {
+        self.original
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        restore_attributes(self.fd, &self.original);
+    }
+}
+
+/// Sets back the attributes of a terminal, ignoring failures since it's only
+/// done while cleaning up.
+pub fn restore_attributes(fd: RawFd, attributes: &termios) {
+    unsafe { tcsetattr(fd, TCSANOW, attributes) };
+}
@@ -0,0 +1,388 @@
+use libc::{ioctl, winsize, STDIN_FILENO, STDOUT_FILENO, TIOCGWINSZ};
+use raw_mode::{restore_attributes, RawMode};
+use std::io::{self, stdout, Stdout, Write};
+use std::mem::zeroed;
+use std::os::unix::io::RawFd;
+use tokterm_core::drawing::cell_buffer::CellBuffer;
+use tokterm_core::drawing::palette::Palette;
+use tokterm_core::drawing::point_2d::Point2d;
+use tokterm_core::drawing::rgb::Rgb;
+use tokterm_core::drawing::sgr::get_sgr;
+use tokterm_core::drawing::size_2d::Size2d;
+use tokterm_core::system::capabilities::{Capabilities, ColorDepth, MouseSupport};
+use tokterm_core::system::clipboard::{get_copy_sequence, CLIPBOARD_REQUEST};
+use tokterm_core::system::cursor::{
+    get_color_sequence, get_style_sequence, CursorStyle, CURSOR_RESET_SEQUENCE,
+};
+use tokterm_core::system::restore::{clear_restore_handler, set_restore_handler};
+use tokterm_core::system::signals::{watch_suspend, watch_window_resize};
+use tokterm_core::system::terminal::Terminal;
+use tokterm_core::system::title::TitleStack;
+use tokterm_core::Error;
+use tokterm_core::Result;
+
+/// Resets the colors, disables the mouse and focus reports, bracketed paste
+/// and the kitty keyboard protocol, and shows the cursor.
+const RESET_SEQUENCE: &str = "\x1b[0m\x1b[?1006l\x1b[?1003l\x1b[?1004l\x1b[?2004l\x1b[<u\x1b[?25h";
+const ENTER_ALTERNATE_SCREEN: &str = "\x1b[?1049h";
+const LEAVE_ALTERNATE_SCREEN: &str = "\x1b[?1049l";
+
+/// A terminal driven with plain ANSI escape sequences over any writer.
+pub struct AnsiTerminal<W: Write> {
+    output: W,
+    fd: Option<RawFd>,
+    size: Size2d,
+    palette: Option<Palette>,
+    raw_mode: Option<RawMode>,
+    capabilities: Capabilities,
+    alternate_screen: bool,
+    cursor_style: Option<CursorStyle>,
+    cursor_color: Option<Rgb>,
+    titles: TitleStack,
+}
+
+impl AnsiTerminal<Stdout> {
+    /// Creates a terminal over the standard output, putting the standard
+    /// input in raw mode. The terminal is restored when dropped, or before
+    /// the message of a panic is printed.
+    pub fn create() -> Result<AnsiTerminal<Stdout>> {
+        let raw_mode = RawMode::enable(STDIN_FILENO)?;
+        let size = get_window_size(STDOUT_FILENO)?;
+        let mut terminal = AnsiTerminal::new(stdout(), size);
+        let (fd, original) = (raw_mode.get_fd(), raw_mode.get_original());
+
+        set_restore_handler(Box::new(move || {
+            let mut output = stdout();
+            let _ = output.write_all(RESET_SEQUENCE.as_bytes());
+            let _ = output.write_all(CURSOR_RESET_SEQUENCE.as_bytes());
+            let _ = output.write_all(LEAVE_ALTERNATE_SCREEN.as_bytes());
+            let _ = output.flush();
+            restore_attributes(fd, &original);
+        }));
+
+        watch_window_resize()?;
+        watch_suspend()?;
+
+        terminal.fd = Some(STDOUT_FILENO);
+        terminal.raw_mode = Some(raw_mode);
+        terminal.capabilities = Capabilities::from_env();
+
+        Ok(terminal)
+    }
+}
+
+impl<W: Write> AnsiTerminal<W> {
+    /// Creates a terminal over a writer, like a PTY, a file or a socket,
+    /// with a fixed console size. The remote terminal is assumed to be a
+    /// basic one with SGR mouse support until told otherwise.
+    pub fn new(output: W, size: Size2d) -> AnsiTerminal<W> {
+        AnsiTerminal {
+            output,
+            fd: None,
+            size,
+            palette: None,
+            raw_mode: None,
+            capabilities: Capabilities {
+                mouse: MouseSupport::Sgr,
+                ..Capabilities::basic()
+            },
+            alternate_screen: false,
+            cursor_style: None,
+            cursor_color: None,
+            titles: TitleStack::new(),
+        }
+    }
+
+    /// Sets the capabilities of the terminal on the other side of the writer.
+    #[inline]
+    pub fn set_capabilities(&mut self, capabilities: Capabilities) {
+        self.capabilities = capabilities;
+    }
+
+    /// Sets the console size reported by writers without a window size,
+    /// like sockets or files.
+    #[inline]
+    pub fn set_console_size(&mut self, size: Size2d) {
+        self.size = size;
+    }
+
+    /// Switches to the alternate screen, which keeps the contents of the
+    /// main one until it is left, or switches back to the main screen.
+    pub fn set_alternate_screen(&mut self, enabled: bool) -> Result<()> {
+        self.write_sequence(if enabled {
+            ENTER_ALTERNATE_SCREEN
+        } else {
+            LEAVE_ALTERNATE_SCREEN
+        })?;
+
+        self.alternate_screen = enabled;
+        Ok(())
+    }
+
+    #[inline]
+    pub fn is_alternate_screen(&self) -> bool {
+        self.alternate_screen
+    }
+
+    /// Gives the terminal back to the shell before the process is stopped,
+    /// resetting it and leaving the alternate screen and the raw mode.
+    pub fn suspend(&mut self) -> Result<()> {
+        self.write_sequence(RESET_SEQUENCE)?;
+        self.restore_cursor()?;
+
+        if self.alternate_screen {
+            self.write_sequence(LEAVE_ALTERNATE_SCREEN)?;
+        }
+
+        if let Some(ref raw_mode) = self.raw_mode {
+            raw_mode.suspend();
+        }
+
+        Ok(())
+    }
+
+    /// Takes the terminal again once the process is continued, entering the
+    /// raw mode and the alternate screen it was in. The screen is cleared,
+    /// so everything has to be drawn again.
+    pub fn resume(&mut self) -> Result<()> {
+        if let Some(ref raw_mode) = self.raw_mode {
+            raw_mode.resume();
+        }
+
+        if self.alternate_screen {
+            self.write_sequence(ENTER_ALTERNATE_SCREEN)?;
+        }
+
+        self.apply_cursor()?;
+        self.clear()
+    }
+
+    /// Restores the user's cursor, keeping the style and color to apply them
+    /// again when resumed.
+    fn restore_cursor(&mut self) -> Result<()> {
+        if self.cursor_style.is_some() {
+            self.write_sequence(&get_style_sequence(None))?;
+        }
+
+        if self.cursor_color.is_some() {
+            self.write_sequence(&get_color_sequence(None))?;
+        }
+
+        Ok(())
+    }
+
+    fn apply_cursor(&mut self) -> Result<()> {
+        if let Some(style) = self.cursor_style {
+            self.write_sequence(&get_style_sequence(Some(style)))?;
+        }
+
+        if let Some(color) = self.cursor_color {
+            self.write_sequence(&get_color_sequence(Some(color)))?;
+        }
+
+        Ok(())
+    }
+
+    /// Gets the file descriptor whose window size is reported, if the
+    /// terminal is a real one.
+    #[inline]
+    pub fn get_fd(&self) -> Option<RawFd> {
+        self.fd
+    }
+
+    #[inline]
+    pub fn get_output(&mut self) -> &mut W {
+        &mut self.output
+    }
+
+    /// Writes a raw escape sequence and flushes it.
+    pub fn write_sequence(&mut self, sequence: &str) -> Result<()> {
+        if let Err(error) = self.output.write_all(sequence.as_bytes()) {
+            return Err(Error::io("Couldn't write to the terminal.", error));
+        }
+
+        if let Err(error) = self.output.flush() {
+            return Err(Error::io("Couldn't flush the buffer.", error));
+        }
+
+        Ok(())
+    }
+}
+
+impl<W: Write> Drop for AnsiTerminal<W> {
+    fn drop(&mut self) {
+        let title = self.titles.restore();
+        let _ = self.write_sequence(RESET_SEQUENCE);
+        let _ = self.restore_cursor();
+        let _ = self.write_sequence(&title);
+
+        if self.alternate_screen {
+            let _ = self.set_alternate_screen(false);
+        }
+
+        // the raw mode is left right after, when the field is dropped.
+        if self.raw_mode.is_some() {
+            clear_restore_handler();
+        }
+    }
+}
+
+impl<W: Write> Terminal for AnsiTerminal<W> {
+    /// Shows or hides the cursor.
+    fn set_cursor_visibility(&mut self, visible: bool) -> Result<()> {
+        self.write_sequence(if visible { "\x1b[?25h" } else { "\x1b[?25l" })
+    }
+
+    /// Moves the console cursor to a given position.
+    fn set_cursor(&mut self, position: Point2d) -> Result<()> {
+        self.write_sequence(&format!("\x1b[{};{}H", position.y + 1, position.x + 1))
+    }
+
+    /// Sets the shape of the cursor and whether it blinks, or restores the
+    /// user's own cursor when `None` is given.
+    fn set_cursor_style(&mut self, style: Option<CursorStyle>) -> Result<()> {
+        self.write_sequence(&get_style_sequence(style))?;
+        self.cursor_style = style;
+        Ok(())
+    }
+
+    /// Sets the color of the cursor, or restores the user's own color when
+    /// `None` is given.
+    fn set_cursor_color(&mut self, color: Option<Rgb>) -> Result<()> {
+        self.write_sequence(&get_color_sequence(color))?;
+        self.cursor_color = color;
+        Ok(())
+    }
+
+    /// Sets the window title, which also labels the tab of the terminal.
+    /// The user's title is restored on exit.
+    fn set_title(&mut self, title: &str) -> Result<()> {
+        if !self.capabilities.title {
+            return Err(Error::NotSupported(
+                "The terminal can not change its title.",
+            ));
+        }
+
+        let sequence = self.titles.set_title(title);
+        self.write_sequence(&sequence)
+    }
+
+    /// Saves the current title, so it can be restored with `pop_title`.
+    fn push_title(&mut self) -> Result<()> {
+        if !self.capabilities.title {
+            return Err(Error::NotSupported(
+                "The terminal can not change its title.",
+            ));
+        }
+
+        let sequence = self.titles.push_title();
+        self.write_sequence(&sequence)
+    }
+
+    /// Restores the last title saved with `push_title`.
+    fn pop_title(&mut self) -> Result<()> {
+        let sequence = self.titles.pop_title()?;
+        self.write_sequence(&sequence)
+    }
+
+    /// Copies a text to the system clipboard.
+    fn copy_to_clipboard(&mut self, text: &str) -> Result<()> {
+        if !self.capabilities.clipboard {
+            return Err(Error::NotSupported(
+                "The terminal can not access the clipboard.",
+            ));
+        }
+
+        self.write_sequence(&get_copy_sequence(text))
+    }
+
+    /// Asks for the text of the system clipboard, which arrives later as an
+    /// `Event::Clipboard`. Terminals may ignore the request, for instance
+    /// when the user didn't allow reading the clipboard.
+    fn request_clipboard(&mut self) -> Result<()> {
+        if !self.capabilities.clipboard {
+            return Err(Error::NotSupported(
+                "The terminal can not access the clipboard.",
+            ));
+        }
+
+        self.write_sequence(CLIPBOARD_REQUEST)
+    }
+
+    /// Gets the current console size in character units.
+    fn get_console_size(&self) -> Result<Size2d> {
+        match self.fd {
+            Some(fd) => get_window_size(fd),
+            None => Ok(self.size),
+        }
+    }
+
+    /// Clears the console screen.
+    fn clear(&mut self) -> Result<()> {
+        self.write_sequence("\x1b[2J")
+    }
+
+    /// Draws a `CellBuffer` to the screen.
+    fn write(&mut self, cell_buffer: &mut CellBuffer) -> Result<()> {
+        let width = cell_buffer.size.width;
+        let mut buffer = String::with_capacity(width * cell_buffer.size.height * 2);
+        let mut colors = None;
+        let has_color = self.capabilities.has_color();
+        // palettes need true color, otherwise the base colors are kept.
+        let palette = match self.capabilities.color_depth {
+            ColorDepth::TrueColor => self.palette.as_ref(),
+            _ => None,
+        };
+
+        for (index, cell) in cell_buffer.iter().enumerate() {
+            if index % width == 0 {
+                buffer += &format!("\x1b[{};1H", index / width + 1);
+            }
+
+            if has_color && colors != Some((cell.foreground, cell.background)) {
+                colors = Some((cell.foreground, cell.background));
+                buffer += &get_sgr(cell.foreground, cell.background, palette);
+            }
+
+            buffer.push(cell.character);
+        }
+
+        self.write_sequence(&buffer)
+    }
+
+    /// Sets the palette used to present the base colors, or restores the
+    /// terminal's own colors when `None` is given.
+    fn set_palette(&mut self, palette: Option<&Palette>) -> Result<()> {
+        // palettes are drawn with true color, restoring the own colors always
+        // works.
+        if palette.is_some() && self.capabilities.color_depth != ColorDepth::TrueColor {
+            return Err(Error::NotSupported(
+                "The terminal can not change its colors.",
+            ));
+        }
+
+        self.palette = palette.cloned();
+        Ok(())
+    }
+
+    /// Gets what the terminal supports, detected from the environment unless
+    /// the backend knows better.
+    #[inline]
+    fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+}
+
+/// Queries the window size of a terminal file descriptor.
+fn get_window_size(fd: RawFd) -> Result<Size2d> {
+    let mut size: winsize = unsafe { zeroed() };
+
+    if unsafe { ioctl(fd, TIOCGWINSZ, &mut size) } != 0 {
+        return Err(Error::io(
+            "Couldn't retrieve the terminal size.",
+            io::Error::last_os_error(),
+        ));
+    }
+
+    Ok(Size2d::new(size.ws_col as usize, size.ws_row as usize))
+}
@@ -0,0 +1,80 @@
+extern crate tokterm_ansi;
+extern crate tokterm_core;
+
+use std::io::Cursor;
+use std::thread;
+use std::time::Duration;
+use tokterm_ansi::application::AnsiApplication;
+use tokterm_ansi::terminal::AnsiTerminal;
+use tokterm_core::drawing::size_2d::Size2d;
+use tokterm_core::events::event::{Event, KeyboardEventType};
+use tokterm_core::input::key::Key;
+use tokterm_core::system::application::Application;
+use tokterm_core::system::capabilities::{Capabilities, MouseSupport};
+use tokterm_core::Error;
+
+/// Listens until the reader thread reaches the end of the input, gathering
+/// the events on the way.
+fn listen_until_closed<W: ::std::io::Write>(application: &mut AnsiApplication<W>) -> Vec<Event> {
+    let mut events = Vec::new();
+
+    for _ in 0..100 {
+        let result = application.listen_events();
+
+        while let Some(event) = application.get_mut_event_queue().get_event() {
+            events.push(event);
+        }
+
+        match result {
+            Ok(()) => thread::sleep(Duration::from_millis(10)),
+            Err(Error::TerminalClosed) => return events,
+            Err(error) => panic!("unexpected error: {:?}", error),
+        }
+    }
+
+    panic!("the input was never closed");
+}
+
+#[test]
+fn input_is_parsed_until_it_ends() {
+    let mut output = Vec::new();
+    {
+        let terminal = AnsiTerminal::new(&mut output, Size2d::new(80, 24));
+        let input = Cursor::new(b"q".to_vec());
+        let mut application = AnsiApplication::new(input, terminal).unwrap();
+
+        let events = listen_until_closed(&mut application);
+        match events.first() {
+            Some(Event::Keyboard(keyboard)) => {
+                assert_eq!(keyboard.event_type, KeyboardEventType::KeyDown);
+                assert_eq!(keyboard.key, Key::Q);
+                assert_eq!(keyboard.character, 'q');
+            }
+            event => panic!("unexpected event: {:?}", event),
+        }
+    }
+
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.starts_with("\x1b[?1003h\x1b[?1006h"));
+}
+
+#[test]
+fn alternate_screen_is_entered_and_left() {
+    let mut output = Vec::new();
+    {
+        let mut terminal = AnsiTerminal::new(&mut output, Size2d::new(80, 24));
+
+        terminal.set_capabilities(Capabilities {
+            alternate_screen: true,
+            mouse: MouseSupport::Sgr,
+            ..Capabilities::basic()
+        });
+
+        let application = AnsiApplication::new(Cursor::new(Vec::new()), terminal).unwrap();
+        assert!(application.get_terminal().capabilities().alternate_screen);
+    }
+
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.starts_with("\x1b[?1049h\x1b[?1003h\x1b[?1006h"));
+    assert!(output.ends_with("\x1b[?1049l"));
+}
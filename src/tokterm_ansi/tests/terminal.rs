@@ -0,0 +1,94 @@
+extern crate tokterm_ansi;
+extern crate tokterm_core;
+
+use tokterm_ansi::terminal::AnsiTerminal;
+use tokterm_core::drawing::size_2d::Size2d;
+use tokterm_core::system::capabilities::Capabilities;
+use tokterm_core::system::cursor::{CursorShape, CursorStyle};
+use tokterm_core::system::terminal::Terminal;
+use tokterm_core::Error;
+
+const RESET_SEQUENCE: &str = "\x1b[0m\x1b[?1006l\x1b[?1003l\x1b[?1004l\x1b[?2004l\x1b[<u\x1b[?25h";
+
+fn take_output(terminal: &mut AnsiTerminal<&mut Vec<u8>>) -> String {
+    let output = String::from_utf8(terminal.get_output().to_vec()).unwrap();
+
+    terminal.get_output().clear();
+    output
+}
+
+#[test]
+fn titles_are_pushed_and_restored_on_drop() {
+    let mut output = Vec::new();
+    {
+        let mut terminal = AnsiTerminal::new(&mut output, Size2d::new(80, 24));
+
+        match terminal.set_title("app") {
+            Err(Error::NotSupported(_)) => (),
+            result => panic!("unexpected result: {:?}", result),
+        }
+        assert_eq!(take_output(&mut terminal), "");
+
+        terminal.set_capabilities(Capabilities {
+            title: true,
+            ..Capabilities::basic()
+        });
+
+        terminal.set_title("app").unwrap();
+        assert_eq!(take_output(&mut terminal), "\x1b[22;0t\x1b]0;app\x07");
+
+        terminal.push_title().unwrap();
+        terminal.set_title("dialog\x07").unwrap();
+        assert_eq!(take_output(&mut terminal), "\x1b[22;0t\x1b]0;dialog\x07");
+
+        terminal.pop_title().unwrap();
+        assert_eq!(take_output(&mut terminal), "\x1b[23;0t\x1b]0;app\x07");
+        assert!(terminal.pop_title().is_err());
+
+        terminal.push_title().unwrap();
+        take_output(&mut terminal);
+    }
+
+    // the title still pushed and the user's one are both popped.
+    let output = String::from_utf8(output).unwrap();
+    assert_eq!(output, format!("{}\x1b[23;0t\x1b[23;0t", RESET_SEQUENCE));
+}
+
+#[test]
+fn cursor_style_is_reset_on_drop() {
+    let mut output = Vec::new();
+    {
+        let mut terminal = AnsiTerminal::new(&mut output, Size2d::new(80, 24));
+
+        terminal
+            .set_cursor_style(Some(CursorStyle::new(CursorShape::Bar, true)))
+            .unwrap();
+        assert_eq!(take_output(&mut terminal), "\x1b[5 q");
+    }
+
+    let output = String::from_utf8(output).unwrap();
+    assert_eq!(output, format!("{}\x1b[0 q", RESET_SEQUENCE));
+}
+
+#[test]
+fn untouched_terminals_are_only_reset_on_drop() {
+    let mut output = Vec::new();
+
+    AnsiTerminal::new(&mut output, Size2d::new(80, 24));
+    assert_eq!(String::from_utf8(output).unwrap(), RESET_SEQUENCE);
+}
+
+#[test]
+fn alternate_screen_is_left_on_drop() {
+    let mut output = Vec::new();
+    {
+        let mut terminal = AnsiTerminal::new(&mut output, Size2d::new(80, 24));
+
+        terminal.set_alternate_screen(true).unwrap();
+        assert!(terminal.is_alternate_screen());
+        assert_eq!(take_output(&mut terminal), "\x1b[?1049h");
+    }
+
+    let output = String::from_utf8(output).unwrap();
+    assert_eq!(output, format!("{}\x1b[?1049l", RESET_SEQUENCE));
+}
@@ -0,0 +1,486 @@
+use std::cmp::Ordering;
+use std::io::{BufRead, Write};
+use std::iter::Peekable;
+use std::str::Chars;
+use tokterm_core::drawing::size_2d::Size2d;
+use tokterm_core::Error;
+use tokterm_core::Result;
+
+/// The largest width or height taken from a recording, far beyond any real
+/// terminal while keeping its screen allocatable.
+const MAX_SIZE: usize = 4096;
+
+/// The header of an asciicast v2 recording.
+#[derive(Debug, Copy, Clone)]
+pub struct CastHeader {
+    pub size: Size2d,
+    pub timestamp: Option<u64>,
+}
+
+/// An event of an asciicast v2 recording, like an output (`o`) or a resize
+/// (`r`).
+#[derive(Debug, Clone)]
+pub struct CastEvent {
+    pub time: f64,
+    pub code: String,
+    pub data: String,
+}
+
+/// Reads a whole asciicast v2 recording. Events are sorted by time.
+pub fn read_cast<R: BufRead>(input: R) -> Result<(CastHeader, Vec<CastEvent>)> {
+    let mut lines = input.lines();
+    let header = match lines.next() {
+        Some(Ok(line)) => parse_header(&line)?,
+        Some(Err(error)) => return Err(Error::io("Couldn't read the recording.", error)),
+        None => return Err(Error::InvalidArgument("The recording is empty.")),
+    };
+    let mut events = Vec::new();
+
+    for line in lines {
+        let line = match line {
+            Ok(line) => line,
+            Err(error) => return Err(Error::io("Couldn't read the recording.", error)),
+        };
+
+        if !line.trim().is_empty() {
+            events.push(parse_event(&line)?);
+        }
+    }
+
+    events.sort_by(|a, b| a.time.partial_cmp(&b.time).unwrap_or(Ordering::Equal));
+
+    Ok((header, events))
+}
+
+/// Parses the asciicast v2 header line.
+pub fn parse_header(line: &str) -> Result<CastHeader> {
+    let fields = match parse_json(line) {
+        Some(Value::Object(fields)) => fields,
+        _ => return Err(Error::InvalidArgument("Malformed asciicast header.")),
+    };
+    let field = |name: &str| {
+        fields
+            .iter()
+            .find(|field| field.0 == name)
+            .map(|field| &field.1)
+    };
+
+    match field("version") {
+        Some(Value::Number(version)) if *version == 2.0 => (),
+        _ => {
+            return Err(Error::NotSupported(
+                "Only asciicast v2 recordings are supported.",
+            ))
+        }
+    }
+
+    let size = match (field("width"), field("height")) {
+        (Some(Value::Number(width)), Some(Value::Number(height))) => {
+            match (get_dimension(*width), get_dimension(*height)) {
+                (Some(width), Some(height)) => Size2d::new(width, height),
+                _ => {
+                    return Err(Error::InvalidArgument(
+                        "The asciicast terminal size is out of range.",
+                    ))
+                }
+            }
+        }
+        _ => return Err(Error::InvalidArgument("Malformed asciicast header.")),
+    };
+
+    Ok(CastHeader {
+        size,
+        timestamp: match field("timestamp") {
+            Some(Value::Number(timestamp)) => Some(*timestamp as u64),
+            _ => None,
+        },
+    })
+}
+
+/// Parses an asciicast v2 event line.
+pub fn parse_event(line: &str) -> Result<CastEvent> {
+    let mut items = match parse_json(line) {
+        Some(Value::Array(items)) => items.into_iter(),
+        _ => return Err(Error::InvalidArgument("Malformed asciicast event.")),
+    };
+
+    match (items.next(), items.next(), items.next()) {
+        (Some(Value::Number(time)), Some(Value::String(code)), Some(Value::String(data))) => {
+            Ok(CastEvent { time, code, data })
+        }
+        _ => Err(Error::InvalidArgument("Malformed asciicast event.")),
+    }
+}
+
+/// Parses the size of a resize (`r`) event, like `80x24`. Empty sizes and
+/// sizes over `MAX_SIZE` are refused.
+pub fn parse_size(data: &str) -> Option<Size2d> {
+    let mut parts = data.splitn(2, 'x');
+
+    match (parts.next(), parts.next()) {
+        (Some(width), Some(height)) => match (width.trim().parse(), height.trim().parse()) {
+            (Ok(width), Ok(height))
+                if (1..=MAX_SIZE).contains(&width) && (1..=MAX_SIZE).contains(&height) =>
+            {
+                Some(Size2d::new(width, height))
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Gets a width or height of the header, from 1 to `MAX_SIZE`.
+fn get_dimension(value: f64) -> Option<usize> {
+    if value.is_finite() && value >= 1.0 && value <= MAX_SIZE as f64 {
+        Some(value as usize)
+    } else {
+        None
+    }
+}
+
+/// Writes the asciicast v2 header line.
+pub fn write_header<W: Write>(output: &mut W, size: Size2d, timestamp: u64) -> Result<()> {
+    write_line(
+        output,
+        &format!(
+            "{{\"version\": 2, \"width\": {}, \"height\": {}, \"timestamp\": {}}}",
+            size.width, size.height, timestamp
+        ),
+    )
+}
+
+/// Writes an asciicast v2 event line, like `[1.250000, "o", "text"]`.
+pub fn write_event<W: Write>(output: &mut W, time: f64, code: &str, data: &str) -> Result<()> {
+    write_line(
+        output,
+        &format!(
+            "[{:.6}, {}, {}]",
+            time,
+            escape_string(code),
+            escape_string(data)
+        ),
+    )
+}
+
+/// Quotes a string as a JSON string literal.
+pub fn escape_string(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len() + 2);
+
+    escaped.push('"');
+
+    for character in text.chars() {
+        match character {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 || c == '\u{7f}' => {
+                escaped.push_str(&format!("\\u{:04x}", c as u32))
+            }
+            c => escaped.push(c),
+        }
+    }
+
+    escaped.push('"');
+    escaped
+}
+
+fn write_line<W: Write>(output: &mut W, line: &str) -> Result<()> {
+    if let Err(error) = writeln!(output, "{}", line) {
+        return Err(Error::io("Couldn't write the recording.", error));
+    }
+
+    Ok(())
+}
+
+/// The JSON values found in asciicast lines.
+enum Value {
+    /// `true`, `false` or `null`, never needed by the recordings.
+    Literal,
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+/// Parses a JSON document, returning `None` when it is malformed.
+fn parse_json(text: &str) -> Option<Value> {
+    let mut chars = text.chars().peekable();
+    let value = parse_value(&mut chars)?;
+
+    skip_whitespace(&mut chars);
+
+    match chars.next() {
+        Some(_) => None,
+        None => Some(value),
+    }
+}
+
+fn parse_value(chars: &mut Peekable<Chars>) -> Option<Value> {
+    skip_whitespace(chars);
+
+    match *chars.peek()? {
+        '{' => {
+            chars.next();
+            let mut fields = Vec::new();
+
+            if !consume(chars, '}') {
+                loop {
+                    skip_whitespace(chars);
+                    let name = parse_string(chars)?;
+
+                    if !consume(chars, ':') {
+                        return None;
+                    }
+
+                    fields.push((name, parse_value(chars)?));
+
+                    if consume(chars, '}') {
+                        break;
+                    } else if !consume(chars, ',') {
+                        return None;
+                    }
+                }
+            }
+
+            Some(Value::Object(fields))
+        }
+        '[' => {
+            chars.next();
+            let mut items = Vec::new();
+
+            if !consume(chars, ']') {
+                loop {
+                    items.push(parse_value(chars)?);
+
+                    if consume(chars, ']') {
+                        break;
+                    } else if !consume(chars, ',') {
+                        return None;
+                    }
+                }
+            }
+
+            Some(Value::Array(items))
+        }
+        '"' => parse_string(chars).map(Value::String),
+        't' => parse_literal(chars, "true", Value::Literal),
+        'f' => parse_literal(chars, "false", Value::Literal),
+        'n' => parse_literal(chars, "null", Value::Literal),
+        _ => {
+            let mut number = String::new();
+
+            while let Some(&c) = chars.peek() {
+                if c.is_ascii_digit() || c == '-' || c == '+' || c == '.' || c == 'e' || c == 'E' {
+                    number.push(c);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+
+            number.parse().ok().map(Value::Number)
+        }
+    }
+}
+
+fn parse_string(chars: &mut Peekable<Chars>) -> Option<String> {
+    if chars.next()? != '"' {
+        return None;
+    }
+
+    let mut text = String::new();
+
+    loop {
+        match chars.next()? {
+            '"' => return Some(text),
+            '\\' => match chars.next()? {
+                'n' => text.push('\n'),
+                'r' => text.push('\r'),
+                't' => text.push('\t'),
+                'b' => text.push('\u{8}'),
+                'f' => text.push('\u{c}'),
+                'u' => {
+                    let mut code = parse_hex(chars)?;
+
+                    // joins UTF-16 surrogate pairs, a low surrogate alone
+                    // isn't a character and is refused below.
+                    if (0xd800..0xdc00).contains(&code) {
+                        if chars.next()? != '\\' || chars.next()? != 'u' {
+                            return None;
+                        }
+
+                        let low = parse_hex(chars)?;
+
+                        if !(0xdc00..0xe000).contains(&low) {
+                            return None;
+                        }
+
+                        code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+                    }
+
+                    text.push(::std::char::from_u32(code)?);
+                }
+                c => text.push(c),
+            },
+            c => text.push(c),
+        }
+    }
+}
+
+fn parse_hex(chars: &mut Peekable<Chars>) -> Option<u32> {
+    let mut code = 0;
+
+    for _ in 0..4 {
+        code = code * 16 + chars.next()?.to_digit(16)?;
+    }
+
+    Some(code)
+}
+
+fn parse_literal(chars: &mut Peekable<Chars>, literal: &str, value: Value) -> Option<Value> {
+    for expected in literal.chars() {
+        if chars.next()? != expected {
+            return None;
+        }
+    }
+
+    Some(value)
+}
+
+fn consume(chars: &mut Peekable<Chars>, expected: char) -> bool {
+    skip_whitespace(chars);
+
+    if chars.peek() == Some(&expected) {
+        chars.next();
+        true
+    } else {
+        false
+    }
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while chars.peek().is_some_and(|c| c.is_whitespace()) {
+        chars.next();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_data(data: &str) -> Option<String> {
+        parse_event(&format!("[0.5, \"o\", \"{}\"]", data))
+            .ok()
+            .map(|event| event.data)
+    }
+
+    #[test]
+    fn string_escapes() {
+        assert_eq!(
+            parse_data(r#"a\"\\\/\n\r\t\b\f"#),
+            Some(String::from("a\"\\/\n\r\t\u{8}\u{c}"))
+        );
+        assert_eq!(parse_data(r"\u001b[0mé"), Some(String::from("\x1b[0mé")));
+        assert_eq!(parse_data(r"😀"), Some(String::from("😀")));
+        assert_eq!(parse_data(r"\u12"), None);
+        assert_eq!(parse_data(r"\uzzzz"), None);
+    }
+
+    #[test]
+    fn broken_surrogates_are_refused() {
+        assert_eq!(parse_data(r"\ud800A"), None);
+        assert_eq!(parse_data(r"\ud800\ud800"), None);
+        assert_eq!(parse_data(r"\ud800 \udc00"), None);
+        assert_eq!(parse_data(r"\ud800"), None);
+        assert_eq!(parse_data(r"\udc00"), None);
+        assert_eq!(parse_data(r"\udfff\ud800"), None);
+    }
+
+    #[test]
+    fn written_events_are_read_back() {
+        let mut output = Vec::new();
+        let data = "\x1b[1;1H\"quoted\"\\\r\n\t\x7f😀";
+
+        write_event(&mut output, 1.25, "o", data).unwrap();
+        let line = String::from_utf8(output).unwrap();
+        let event = parse_event(line.trim_end()).unwrap();
+
+        assert_eq!(event.time, 1.25);
+        assert_eq!(event.code, "o");
+        assert_eq!(event.data, data);
+    }
+
+    #[test]
+    fn headers() {
+        let header =
+            parse_header(r#"{"version": 2, "width": 80, "height": 24, "timestamp": 1}"#).unwrap();
+        assert_eq!(header.size, Size2d::new(80, 24));
+        assert_eq!(header.timestamp, Some(1));
+
+        match parse_header(r#"{"version": 1, "width": 80, "height": 24}"#) {
+            Err(Error::NotSupported(_)) => (),
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        assert!(parse_header(r#"{"version": 2, "width": 80}"#).is_err());
+        assert!(parse_header(r#"{"version": 2, "width": 80, "height": 24"#).is_err());
+    }
+
+    #[test]
+    fn header_sizes_out_of_range_are_refused() {
+        let sizes = [
+            "0, \"height\": 24",
+            "80, \"height\": 0",
+            "-1, \"height\": 24",
+            "1e300, \"height\": 24",
+            "80, \"height\": 4097",
+        ];
+
+        for size in &sizes {
+            match parse_header(&format!("{{\"version\": 2, \"width\": {}}}", size)) {
+                Err(Error::InvalidArgument(_)) => (),
+                result => panic!("unexpected result for {}: {:?}", size, result),
+            }
+        }
+
+        let header = parse_header(r#"{"version": 2, "width": 4096, "height": 1}"#).unwrap();
+        assert_eq!(header.size, Size2d::new(4096, 1));
+    }
+
+    #[test]
+    fn resize_sizes() {
+        assert_eq!(parse_size("80x24"), Some(Size2d::new(80, 24)));
+        assert_eq!(parse_size(" 80 x 24 "), Some(Size2d::new(80, 24)));
+        assert_eq!(parse_size("0x24"), None);
+        assert_eq!(parse_size("80x0"), None);
+        assert_eq!(parse_size("80x99999999999999999999999"), None);
+        assert_eq!(parse_size("4097x24"), None);
+        assert_eq!(parse_size("80"), None);
+        assert_eq!(parse_size("-80x24"), None);
+    }
+
+    #[test]
+    fn events_are_sorted_by_time() {
+        let input = "{\"version\": 2, \"width\": 4, \"height\": 2}\n\
+                     [2.0, \"o\", \"b\"]\n\
+                     \n\
+                     [1.0, \"o\", \"a\"]\n";
+        let (header, events) = read_cast(input.as_bytes()).unwrap();
+
+        assert_eq!(header.size, Size2d::new(4, 2));
+        assert_eq!(
+            events
+                .iter()
+                .map(|event| &event.data[..])
+                .collect::<Vec<_>>(),
+            ["a", "b"]
+        );
+        assert!(read_cast("".as_bytes()).is_err());
+        assert!(
+            read_cast("{\"version\": 2, \"width\": 4, \"height\": 2}\n[1.0]".as_bytes()).is_err()
+        );
+    }
+}
@@ -0,0 +1,24 @@
+use tokterm_core::drawing::color::Color;
+use tokterm_core::drawing::rgb::Rgb;
+use tokterm_core::drawing::sgr::color_from_u8;
+
+/// Maps an entry of the xterm 256 color table to the nearest base color.
+pub fn color_from_256(index: u8) -> Color {
+    match index {
+        0..=15 => color_from_u8(index),
+        16..=231 => {
+            let index = index - 16;
+            let level = |value: u8| if value == 0 { 0 } else { 55 + value * 40 };
+
+            Color::from_rgb(Rgb::new(
+                level(index / 36),
+                level(index / 6 % 6),
+                level(index % 6),
+            ))
+        }
+        _ => {
+            let grey = 8 + (index - 232) * 10;
+            Color::from_rgb(Rgb::new(grey, grey, grey))
+        }
+    }
+}
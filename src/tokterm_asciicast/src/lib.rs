@@ -0,0 +1,7 @@
+extern crate tokterm_core;
+
+pub mod cast;
+pub mod color;
+pub mod player;
+pub mod recorder;
+pub mod vt;
@@ -0,0 +1,382 @@
+use cast::{parse_size, read_cast, CastEvent, CastHeader};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+use tokterm_core::drawing::cell::Cell;
+use tokterm_core::drawing::cell_buffer::CellBuffer;
+use tokterm_core::drawing::point_2d::Point2d;
+use tokterm_core::events::event::{Event, KeyboardEventType};
+use tokterm_core::input::key::Key;
+use tokterm_core::system::application::Application;
+use tokterm_core::Error;
+use tokterm_core::Result;
+use vt::VtScreen;
+
+/// The seconds skipped by the seek keys.
+const SEEK_STEP: f64 = 5.0;
+const MIN_SPEED: f64 = 1.0 / 16.0;
+const MAX_SPEED: f64 = 16.0;
+
+/// Plays an asciicast v2 recording, producing `CellBuffer` frames on a
+/// timeline that can be paused, sped up and seeked.
+pub struct CastPlayer {
+    header: CastHeader,
+    events: Vec<CastEvent>,
+    screen: VtScreen,
+    next_event: usize,
+    position: f64,
+    speed: f64,
+    paused: bool,
+    last_update: Option<Instant>,
+}
+
+#[allow(dead_code)]
+impl CastPlayer {
+    /// Loads a `.cast` file.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<CastPlayer> {
+        match File::open(path) {
+            Ok(file) => CastPlayer::from_reader(BufReader::new(file)),
+            Err(error) => Err(Error::io("Couldn't open the recording.", error)),
+        }
+    }
+
+    /// Loads a recording from any reader.
+    pub fn from_reader<R: BufRead>(input: R) -> Result<CastPlayer> {
+        let (header, events) = read_cast(input)?;
+        Ok(CastPlayer::new(header, events))
+    }
+
+    /// Creates a player from an already parsed recording. Events must be
+    /// sorted by time.
+    pub fn new(header: CastHeader, events: Vec<CastEvent>) -> CastPlayer {
+        CastPlayer {
+            header,
+            events,
+            screen: VtScreen::new(header.size),
+            next_event: 0,
+            position: 0.0,
+            speed: 1.0,
+            paused: false,
+            last_update: None,
+        }
+    }
+
+    #[inline]
+    pub fn get_header(&self) -> &CastHeader {
+        &self.header
+    }
+
+    /// Gets the current frame of the recording.
+    #[inline]
+    pub fn get_frame(&self) -> &CellBuffer {
+        self.screen.get_buffer()
+    }
+
+    #[inline]
+    pub fn get_screen(&self) -> &VtScreen {
+        &self.screen
+    }
+
+    /// Gets the length of the recording in seconds.
+    pub fn get_duration(&self) -> f64 {
+        self.events.last().map_or(0.0, |event| event.time)
+    }
+
+    /// Gets the current position in the timeline in seconds.
+    #[inline]
+    pub fn get_position(&self) -> f64 {
+        self.position
+    }
+
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.next_event >= self.events.len()
+    }
+
+    #[inline]
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    #[inline]
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    #[inline]
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    #[inline]
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    #[inline]
+    pub fn get_speed(&self) -> f64 {
+        self.speed
+    }
+
+    /// Sets the playback speed, where `1.0` is the recorded speed.
+    pub fn set_speed(&mut self, speed: f64) {
+        self.speed = speed.clamp(MIN_SPEED, MAX_SPEED);
+    }
+
+    /// Moves the timeline to a position in seconds. Seeking backwards replays
+    /// the recording from the start.
+    pub fn seek(&mut self, position: f64) {
+        let position = position.max(0.0).min(self.get_duration());
+
+        if position < self.position {
+            self.screen = VtScreen::new(self.header.size);
+            self.next_event = 0;
+        }
+
+        while let Some(event) = self.events.get(self.next_event) {
+            if event.time > position {
+                break;
+            }
+
+            match event.code.as_str() {
+                "o" => self.screen.feed(&event.data),
+                "r" => {
+                    if let Some(size) = parse_size(&event.data) {
+                        self.screen.resize(size);
+                    }
+                }
+                _ => (),
+            }
+
+            self.next_event += 1;
+        }
+
+        self.position = position;
+    }
+
+    /// Advances the timeline by the given time, scaled by the speed, unless
+    /// the player is paused.
+    pub fn advance(&mut self, elapsed: Duration) {
+        if !self.paused {
+            let seconds = elapsed.as_secs() as f64 + f64::from(elapsed.subsec_nanos()) / 1e9;
+            let position = self.position + seconds * self.speed;
+
+            self.seek(position);
+        }
+    }
+
+    /// Advances the timeline by the real time elapsed since the last update.
+    pub fn update(&mut self) {
+        let now = Instant::now();
+
+        if let Some(last_update) = self.last_update {
+            self.advance(now - last_update);
+        }
+
+        self.last_update = Some(now);
+    }
+
+    /// Handles the playback keys: space pauses, left and right seek, home and
+    /// end jump to the edges, and up and down change the speed. Returns
+    /// whether the event was used.
+    ///
+    /// Keys are taken on release, since every backend reports one `KeyUp`
+    /// per key press while some of them never report `KeyDown`.
+    pub fn handle_event(&mut self, event: &Event) -> bool {
+        let keyboard = match *event {
+            Event::Keyboard(keyboard) if keyboard.event_type == KeyboardEventType::KeyUp => {
+                keyboard
+            }
+            _ => return false,
+        };
+
+        match (keyboard.key, keyboard.character) {
+            (Key::Space, _) => self.toggle_pause(),
+            (Key::Left, _) => {
+                let position = self.position - SEEK_STEP;
+                self.seek(position);
+            }
+            (Key::Right, _) => {
+                let position = self.position + SEEK_STEP;
+                self.seek(position);
+            }
+            (Key::Home, _) => self.seek(0.0),
+            (Key::End, _) => {
+                let position = self.get_duration();
+                self.seek(position);
+            }
+            (Key::Up, _) | (_, '+') => {
+                let speed = self.speed * 2.0;
+                self.set_speed(speed);
+            }
+            (Key::Down, _) | (_, '-') => {
+                let speed = self.speed / 2.0;
+                self.set_speed(speed);
+            }
+            _ => return false,
+        }
+
+        true
+    }
+
+    /// Draws the current frame onto a buffer at the given position.
+    #[inline]
+    pub fn draw(&self, buffer: &mut CellBuffer, position: Point2d) {
+        buffer.write_cell_buffer(self.get_frame(), position);
+    }
+
+    /// Plays the recording on an application until Escape or Q is pressed.
+    pub fn run(&mut self, application: &mut Application) -> Result<()> {
+        let mut buffer = CellBuffer::new(
+            Cell::default(' '),
+            application.get_terminal().get_console_size()?,
+        );
+
+        application.get_mut_terminal().clear()?;
+        application
+            .get_mut_terminal()
+            .set_cursor_visibility(false)?;
+
+        loop {
+            application.listen_events()?;
+
+            while let Some(event) = application.get_mut_event_queue().get_event() {
+                if let Event::Keyboard(keyboard) = event {
+                    if keyboard.event_type == KeyboardEventType::KeyUp
+                        && (keyboard.key == Key::Escape || keyboard.key == Key::Q)
+                    {
+                        return application.get_mut_terminal().set_cursor_visibility(true);
+                    }
+                }
+
+                self.handle_event(&event);
+            }
+
+            self.update();
+
+            buffer.resize(
+                Cell::default(' '),
+                application.get_terminal().get_console_size()?,
+            );
+            buffer.fill_rect(Cell::default(' '), Point2d::empty(), buffer.size);
+            self.draw(&mut buffer, Point2d::empty());
+            application.get_mut_terminal().write(&mut buffer)?;
+
+            thread::sleep(Duration::from_millis(16));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokterm_core::drawing::size_2d::Size2d;
+    use tokterm_core::events::event::KeyboardEvent;
+
+    fn get_player() -> CastPlayer {
+        let event = |time: f64, code: &str, data: &str| CastEvent {
+            time,
+            code: String::from(code),
+            data: String::from(data),
+        };
+        let header = CastHeader {
+            size: Size2d::new(4, 1),
+            timestamp: None,
+        };
+
+        CastPlayer::new(
+            header,
+            vec![
+                event(0.0, "o", "a"),
+                event(1.0, "o", "b"),
+                event(2.0, "r", "2x1"),
+                event(3.0, "o", "\x1b[2J\rc"),
+                event(10.0, "o", "d"),
+            ],
+        )
+    }
+
+    fn get_text(player: &CastPlayer) -> String {
+        player
+            .get_frame()
+            .iter()
+            .map(|cell| cell.character)
+            .collect()
+    }
+
+    fn get_key_up(key: Key) -> Event {
+        Event::Keyboard(KeyboardEvent {
+            event_type: KeyboardEventType::KeyUp,
+            key,
+            key_code: 0,
+            character: '\0',
+            left_control: false,
+            left_shift: false,
+            left_menu: false,
+            right_control: false,
+            right_shift: false,
+            right_menu: false,
+        })
+    }
+
+    #[test]
+    fn seeking_forwards_plays_the_events() {
+        let mut player = get_player();
+
+        player.seek(1.5);
+        assert_eq!(get_text(&player), "ab  ");
+        assert!(!player.is_finished());
+
+        player.seek(3.0);
+        assert_eq!(get_text(&player), "c ");
+        assert_eq!(player.get_frame().size, Size2d::new(2, 1));
+
+        player.seek(100.0);
+        assert_eq!(player.get_position(), 10.0);
+        assert!(player.is_finished());
+    }
+
+    #[test]
+    fn seeking_backwards_replays_from_the_start() {
+        let mut player = get_player();
+
+        player.seek(3.0);
+        player.seek(1.5);
+        assert_eq!(get_text(&player), "ab  ");
+        assert_eq!(player.get_frame().size, Size2d::new(4, 1));
+
+        player.seek(-1.0);
+        assert_eq!(player.get_position(), 0.0);
+        assert_eq!(get_text(&player), "a   ");
+    }
+
+    #[test]
+    fn keys_seek_and_pause() {
+        let mut player = get_player();
+
+        assert!(player.handle_event(&get_key_up(Key::End)));
+        assert_eq!(player.get_position(), 10.0);
+
+        player.handle_event(&get_key_up(Key::Left));
+        assert_eq!(player.get_position(), 5.0);
+        assert_eq!(get_text(&player), "c ");
+
+        player.handle_event(&get_key_up(Key::Left));
+        assert_eq!(player.get_position(), 0.0);
+        assert_eq!(get_text(&player), "a   ");
+
+        player.handle_event(&get_key_up(Key::Space));
+        player.advance(Duration::from_secs(2));
+        assert_eq!(player.get_position(), 0.0);
+
+        player.handle_event(&get_key_up(Key::Space));
+        player.handle_event(&get_key_up(Key::Up));
+        player.advance(Duration::from_secs(1));
+        assert_eq!(player.get_position(), 2.0);
+        assert_eq!(get_text(&player), "ab");
+
+        assert!(!player.handle_event(&get_key_up(Key::Q)));
+    }
+}
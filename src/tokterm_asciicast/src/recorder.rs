@@ -0,0 +1,283 @@
+use cast::{write_event, write_header};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tokterm_core::drawing::cell::Cell;
+use tokterm_core::drawing::cell_buffer::CellBuffer;
+use tokterm_core::drawing::color::Color;
+use tokterm_core::drawing::palette::Palette;
+use tokterm_core::drawing::point_2d::Point2d;
+use tokterm_core::drawing::rgb::Rgb;
+use tokterm_core::drawing::sgr::get_sgr;
+use tokterm_core::drawing::size_2d::Size2d;
+use tokterm_core::system::capabilities::Capabilities;
+use tokterm_core::system::cursor::{get_color_sequence, get_style_sequence, CursorStyle};
+use tokterm_core::system::terminal::Terminal;
+use tokterm_core::system::title::TitleStack;
+use tokterm_core::Error;
+use tokterm_core::Result;
+
+/// Wraps a `Terminal`, forwarding every call to it while recording the
+/// session as an asciicast v2 stream. Frames are stored as the cells that
+/// changed since the previous one.
+pub struct CastRecorder<T: Terminal, W: Write> {
+    terminal: T,
+    output: W,
+    start: Instant,
+    size: Size2d,
+    previous: Option<CellBuffer>,
+    colors: Option<(Color, Color)>,
+    palette: Option<Palette>,
+    titles: TitleStack,
+}
+
+impl<T: Terminal> CastRecorder<T, BufWriter<File>> {
+    /// Creates a recorder writing to a `.cast` file.
+    pub fn create<P: AsRef<Path>>(
+        terminal: T,
+        path: P,
+    ) -> Result<CastRecorder<T, BufWriter<File>>> {
+        let file = match File::create(path) {
+            Ok(file) => file,
+            Err(error) => return Err(Error::io("Couldn't create the recording.", error)),
+        };
+
+        CastRecorder::new(terminal, BufWriter::new(file))
+    }
+}
+
+impl<T: Terminal, W: Write> CastRecorder<T, W> {
+    /// Creates a recorder over any writer, writing the header right away.
+    pub fn new(terminal: T, mut output: W) -> Result<CastRecorder<T, W>> {
+        let size = terminal.get_console_size()?;
+        let timestamp = match SystemTime::now().duration_since(UNIX_EPOCH) {
+            Ok(duration) => duration.as_secs(),
+            Err(_) => 0,
+        };
+
+        write_header(&mut output, size, timestamp)?;
+
+        Ok(CastRecorder {
+            terminal,
+            output,
+            start: Instant::now(),
+            size,
+            previous: None,
+            colors: None,
+            palette: None,
+            titles: TitleStack::new(),
+        })
+    }
+
+    #[inline]
+    pub fn get_terminal(&self) -> &T {
+        &self.terminal
+    }
+
+    #[inline]
+    pub fn get_mut_terminal(&mut self) -> &mut T {
+        &mut self.terminal
+    }
+
+    /// Flushes the recording and gives back the wrapped terminal.
+    pub fn finish(mut self) -> Result<T> {
+        self.flush()?;
+        Ok(self.terminal)
+    }
+
+    /// Flushes the recording to its writer.
+    pub fn flush(&mut self) -> Result<()> {
+        if let Err(error) = self.output.flush() {
+            return Err(Error::io("Couldn't flush the recording.", error));
+        }
+
+        Ok(())
+    }
+
+    fn record(&mut self, data: &str) -> Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        let time = elapsed(self.start);
+        write_event(&mut self.output, time, "o", data)
+    }
+
+    fn record_resize(&mut self, size: Size2d) -> Result<()> {
+        let time = elapsed(self.start);
+
+        self.size = size;
+        write_event(
+            &mut self.output,
+            time,
+            "r",
+            &format!("{}x{}", size.width, size.height),
+        )
+    }
+
+    /// Builds the output for the cells that changed since the last frame.
+    fn diff(&mut self, cell_buffer: &CellBuffer) -> String {
+        let width = cell_buffer.size.width;
+        let mut data = String::new();
+        let mut cursor = None;
+
+        for (index, cell) in cell_buffer.iter().enumerate() {
+            let unchanged = match self.previous {
+                Some(ref previous) => previous
+                    .get(Point2d::new((index % width) as i32, (index / width) as i32))
+                    .is_some_and(|old| same_cell(&old, cell)),
+                None => false,
+            };
+
+            if unchanged {
+                continue;
+            }
+
+            if cursor != Some(index) {
+                data += &format!("\x1b[{};{}H", index / width + 1, index % width + 1);
+            }
+
+            if self.colors != Some((cell.foreground, cell.background)) {
+                self.colors = Some((cell.foreground, cell.background));
+                data += &get_sgr(cell.foreground, cell.background, self.palette.as_ref());
+            }
+
+            data.push(cell.character);
+            cursor = if (index + 1) % width == 0 {
+                None
+            } else {
+                Some(index + 1)
+            };
+        }
+
+        data
+    }
+}
+
+impl<T: Terminal, W: Write> Terminal for CastRecorder<T, W> {
+    /// Shows or hides the cursor.
+    fn set_cursor_visibility(&mut self, visible: bool) -> Result<()> {
+        self.terminal.set_cursor_visibility(visible)?;
+        self.record(if visible { "\x1b[?25h" } else { "\x1b[?25l" })
+    }
+
+    /// Moves the console cursor to a given position.
+    fn set_cursor(&mut self, position: Point2d) -> Result<()> {
+        self.terminal.set_cursor(position)?;
+        self.record(&format!("\x1b[{};{}H", position.y + 1, position.x + 1))
+    }
+
+    /// Sets the shape of the cursor and whether it blinks, or restores the
+    /// user's own cursor when `None` is given.
+    fn set_cursor_style(&mut self, style: Option<CursorStyle>) -> Result<()> {
+        self.terminal.set_cursor_style(style)?;
+        self.record(&get_style_sequence(style))
+    }
+
+    /// Sets the color of the cursor, or restores the user's own color when
+    /// `None` is given.
+    fn set_cursor_color(&mut self, color: Option<Rgb>) -> Result<()> {
+        self.terminal.set_cursor_color(color)?;
+        self.record(&get_color_sequence(color))
+    }
+
+    /// Sets the window title, which also labels the tab of the terminal.
+    /// The user's title is restored on exit.
+    fn set_title(&mut self, title: &str) -> Result<()> {
+        self.terminal.set_title(title)?;
+        let sequence = self.titles.set_title(title);
+        self.record(&sequence)
+    }
+
+    /// Saves the current title, so it can be restored with `pop_title`.
+    fn push_title(&mut self) -> Result<()> {
+        self.terminal.push_title()?;
+        let sequence = self.titles.push_title();
+        self.record(&sequence)
+    }
+
+    /// Restores the last title saved with `push_title`.
+    fn pop_title(&mut self) -> Result<()> {
+        self.terminal.pop_title()?;
+        let sequence = self.titles.pop_title()?;
+        self.record(&sequence)
+    }
+
+    /// Copies a text to the system clipboard.
+    fn copy_to_clipboard(&mut self, text: &str) -> Result<()> {
+        // the copied text isn't recorded, replaying a cast shouldn't
+        // overwrite the viewer's clipboard.
+        self.terminal.copy_to_clipboard(text)
+    }
+
+    /// Asks for the text of the system clipboard, which arrives later as an
+    /// `Event::Clipboard`. Terminals may ignore the request, for instance
+    /// when the user didn't allow reading the clipboard.
+    fn request_clipboard(&mut self) -> Result<()> {
+        self.terminal.request_clipboard()
+    }
+
+    /// Gets the current console size in character units.
+    fn get_console_size(&self) -> Result<Size2d> {
+        self.terminal.get_console_size()
+    }
+
+    /// Clears the console screen.
+    fn clear(&mut self) -> Result<()> {
+        self.terminal.clear()?;
+        self.previous = None;
+        self.record("\x1b[2J")
+    }
+
+    /// Draws a `CellBuffer` to the screen.
+    fn write(&mut self, cell_buffer: &mut CellBuffer) -> Result<()> {
+        self.terminal.write(cell_buffer)?;
+
+        if cell_buffer.size != self.size {
+            self.record_resize(cell_buffer.size)?;
+            self.previous = None;
+        }
+
+        if cell_buffer.size.width == 0 {
+            return Ok(());
+        }
+
+        let data = self.diff(cell_buffer);
+
+        self.previous = Some(cell_buffer.clone());
+        self.record(&data)
+    }
+
+    /// Sets the palette used to present the base colors, or restores the
+    /// terminal's own colors when `None` is given.
+    fn set_palette(&mut self, palette: Option<&Palette>) -> Result<()> {
+        self.terminal.set_palette(palette)?;
+        self.palette = palette.cloned();
+        self.colors = None;
+        self.previous = None;
+        Ok(())
+    }
+
+    /// Gets what the terminal supports, detected from the environment unless
+    /// the backend knows better.
+    #[inline]
+    fn capabilities(&self) -> Capabilities {
+        self.terminal.capabilities()
+    }
+}
+
+/// Compares the visible parts of two cells.
+#[inline]
+fn same_cell(first: &Cell, second: &Cell) -> bool {
+    first.character == second.character
+        && first.foreground == second.foreground
+        && first.background == second.background
+}
+
+/// Gets the seconds elapsed since the recording started.
+#[inline]
+fn elapsed(start: Instant) -> f64 {
+    let duration = start.elapsed();
+    duration.as_secs() as f64 + f64::from(duration.subsec_nanos()) / 1_000_000_000.0
+}
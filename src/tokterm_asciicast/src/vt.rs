@@ -0,0 +1,630 @@
+use color::color_from_256;
+use tokterm_core::drawing::cell::Cell;
+use tokterm_core::drawing::cell_buffer::CellBuffer;
+use tokterm_core::drawing::color::Color;
+use tokterm_core::drawing::point_2d::Point2d;
+use tokterm_core::drawing::rgb::Rgb;
+use tokterm_core::drawing::sgr::color_from_u8;
+use tokterm_core::drawing::size_2d::Size2d;
+
+/// The states of the escape sequence parser.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum State {
+    Ground,
+    Escape,
+    Charset,
+    Csi,
+    Osc,
+    OscEscape,
+}
+
+/// The largest value a sequence parameter can take, bigger values are clamped
+/// to it so cursor and scroll counts can't overflow.
+const MAX_PARAMETER: usize = 0xffff;
+
+/// A virtual terminal screen, interpreting an ANSI/VT output stream into a
+/// `CellBuffer`. The stream can be fed in chunks, sequences split between
+/// them are kept until completed.
+#[derive(Debug, Clone)]
+pub struct VtScreen {
+    buffer: CellBuffer,
+    alternate: Option<CellBuffer>,
+    x: usize,
+    y: usize,
+    saved: (usize, usize),
+    wrap_pending: bool,
+    top: usize,
+    bottom: usize,
+    foreground: Color,
+    background: Color,
+    inverse: bool,
+    cursor_visible: bool,
+    state: State,
+    params: String,
+}
+
+#[allow(dead_code)]
+impl VtScreen {
+    pub fn new(size: Size2d) -> VtScreen {
+        VtScreen {
+            buffer: CellBuffer::new(Cell::default(' '), size),
+            alternate: None,
+            x: 0,
+            y: 0,
+            saved: (0, 0),
+            wrap_pending: false,
+            top: 0,
+            bottom: size.height.saturating_sub(1),
+            foreground: Color::Grey,
+            background: Color::Black,
+            inverse: false,
+            cursor_visible: true,
+            state: State::Ground,
+            params: String::new(),
+        }
+    }
+
+    /// Gets the current screen contents.
+    #[inline]
+    pub fn get_buffer(&self) -> &CellBuffer {
+        &self.buffer
+    }
+
+    #[inline]
+    pub fn get_cursor(&self) -> Point2d {
+        Point2d::new(self.x as i32, self.y as i32)
+    }
+
+    #[inline]
+    pub fn is_cursor_visible(&self) -> bool {
+        self.cursor_visible
+    }
+
+    /// Resizes the screen, keeping the contents that still fit.
+    pub fn resize(&mut self, size: Size2d) {
+        if size == self.buffer.size {
+            return;
+        }
+
+        let mut buffer = CellBuffer::new(self.blank(), size);
+        buffer.write_cell_buffer(&self.buffer, Point2d::empty());
+
+        self.buffer = buffer;
+        self.alternate = None;
+        self.x = self.x.min(size.width.saturating_sub(1));
+        self.y = self.y.min(size.height.saturating_sub(1));
+        self.wrap_pending = false;
+        self.top = 0;
+        self.bottom = size.height.saturating_sub(1);
+    }
+
+    /// Interprets a chunk of the output stream.
+    pub fn feed(&mut self, data: &str) {
+        if self.buffer.size.width == 0 || self.buffer.size.height == 0 {
+            return;
+        }
+
+        for character in data.chars() {
+            self.feed_char(character);
+        }
+    }
+
+    fn feed_char(&mut self, character: char) {
+        match self.state {
+            State::Ground => self.ground(character),
+            State::Escape => self.escape(character),
+            State::Charset => self.state = State::Ground,
+            State::Csi => match character {
+                '\x1b' => self.state = State::Escape,
+                '\x20'..='\x3f' => self.params.push(character),
+                '\x40'..='\x7e' => {
+                    self.state = State::Ground;
+                    self.csi(character);
+                }
+                _ => self.ground(character),
+            },
+            State::Osc => match character {
+                '\x07' => self.state = State::Ground,
+                '\x1b' => self.state = State::OscEscape,
+                _ => (),
+            },
+            State::OscEscape => match character {
+                '\\' => self.state = State::Ground,
+                _ => self.escape(character),
+            },
+        }
+    }
+
+    fn ground(&mut self, character: char) {
+        match character {
+            '\x1b' => self.state = State::Escape,
+            '\r' => self.carriage_return(),
+            '\n' | '\x0b' | '\x0c' => self.line_feed(),
+            '\x08' => {
+                self.x = self.x.saturating_sub(1);
+                self.wrap_pending = false;
+            }
+            '\t' => {
+                self.x = ((self.x / 8 + 1) * 8).min(self.buffer.size.width - 1);
+                self.wrap_pending = false;
+            }
+            c if c < ' ' || c == '\x7f' => (),
+            c => self.print(c),
+        }
+    }
+
+    fn escape(&mut self, character: char) {
+        self.state = State::Ground;
+
+        match character {
+            '[' => {
+                self.params.clear();
+                self.state = State::Csi;
+            }
+            ']' => self.state = State::Osc,
+            '(' | ')' | '*' | '+' => self.state = State::Charset,
+            '7' => self.saved = (self.x, self.y),
+            '8' => self.restore_cursor(),
+            'D' => self.line_feed(),
+            'E' => {
+                self.carriage_return();
+                self.line_feed();
+            }
+            'M' => self.reverse_line_feed(),
+            'c' => *self = VtScreen::new(self.buffer.size),
+            _ => (),
+        }
+    }
+
+    fn csi(&mut self, command: char) {
+        // sequences with other private prefixes, like `CSI > c` or `CSI < u`,
+        // mean something else entirely.
+        if self.params.starts_with(['<', '>', '=']) {
+            return;
+        }
+
+        let private = self.params.starts_with('?');
+        let params: Vec<usize> = self
+            .params
+            .trim_start_matches('?')
+            .split(';')
+            .map(parse_parameter)
+            .collect();
+        let count = params[0].max(1);
+        let width = self.buffer.size.width;
+        let height = self.buffer.size.height;
+
+        if private {
+            if command == 'h' || command == 'l' {
+                self.set_private_modes(&params, command == 'h');
+            }
+
+            return;
+        }
+
+        self.wrap_pending = false;
+
+        match command {
+            'A' => self.y = self.y.saturating_sub(count).max(self.top.min(self.y)),
+            'B' | 'e' => self.y = self.y.saturating_add(count).min(self.bottom.max(self.y)),
+            'C' | 'a' => self.x = self.x.saturating_add(count).min(width - 1),
+            'D' => self.x = self.x.saturating_sub(count),
+            'E' => {
+                self.x = 0;
+                self.y = self.y.saturating_add(count).min(height - 1);
+            }
+            'F' => {
+                self.x = 0;
+                self.y = self.y.saturating_sub(count);
+            }
+            'G' | '`' => self.x = (count - 1).min(width - 1),
+            'd' => self.y = (count - 1).min(height - 1),
+            'H' | 'f' => {
+                self.y = (count - 1).min(height - 1);
+                self.x = (params.get(1).cloned().unwrap_or(1).max(1) - 1).min(width - 1);
+            }
+            'J' => self.erase_display(params[0]),
+            'K' => self.erase_line(params[0]),
+            'X' => {
+                let blank = self.blank();
+                self.buffer
+                    .fill_rect(blank, self.get_cursor(), Size2d::new(count, 1));
+            }
+            'P' => self.shift_line(-(count as i32)),
+            '@' => self.shift_line(count as i32),
+            'L' => self.shift_lines(count as i32),
+            'M' => self.shift_lines(-(count as i32)),
+            'S' => self.scroll(-(count as i32)),
+            'T' => self.scroll(count as i32),
+            'm' => self.select_graphic_rendition(&params),
+            'r' => {
+                let top = params[0].max(1) - 1;
+                let bottom = params.get(1).cloned().unwrap_or(0);
+                let bottom = if bottom == 0 {
+                    height
+                } else {
+                    bottom.min(height)
+                } - 1;
+
+                if top < bottom {
+                    self.top = top;
+                    self.bottom = bottom;
+                    self.x = 0;
+                    self.y = 0;
+                }
+            }
+            's' => self.saved = (self.x, self.y),
+            'u' => self.restore_cursor(),
+            _ => (),
+        }
+    }
+
+    fn set_private_modes(&mut self, params: &[usize], enable: bool) {
+        for mode in params {
+            match *mode {
+                25 => self.cursor_visible = enable,
+                47 | 1047 | 1049 => {
+                    if enable && self.alternate.is_none() {
+                        let blank = CellBuffer::new(self.blank(), self.buffer.size);
+
+                        if *mode == 1049 {
+                            self.saved = (self.x, self.y);
+                        }
+
+                        self.alternate = Some(::std::mem::replace(&mut self.buffer, blank));
+                    } else if !enable {
+                        if let Some(main) = self.alternate.take() {
+                            self.buffer = main;
+
+                            if *mode == 1049 {
+                                self.restore_cursor();
+                            }
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    fn select_graphic_rendition(&mut self, params: &[usize]) {
+        let mut params = params.iter().cloned();
+
+        while let Some(param) = params.next() {
+            match param {
+                0 => {
+                    self.foreground = Color::Grey;
+                    self.background = Color::Black;
+                    self.inverse = false;
+                }
+                7 => self.inverse = true,
+                27 => self.inverse = false,
+                30..=37 => self.foreground = color_from_u8((param - 30) as u8),
+                39 => self.foreground = Color::Grey,
+                40..=47 => self.background = color_from_u8((param - 40) as u8),
+                49 => self.background = Color::Black,
+                90..=97 => self.foreground = color_from_u8((param - 82) as u8),
+                100..=107 => self.background = color_from_u8((param - 92) as u8),
+                38 | 48 => {
+                    let color = match params.next() {
+                        Some(5) => params.next().map(|index| color_from_256(index as u8)),
+                        Some(2) => match (params.next(), params.next(), params.next()) {
+                            (Some(red), Some(green), Some(blue)) => Some(Color::from_rgb(
+                                Rgb::new(red as u8, green as u8, blue as u8),
+                            )),
+                            _ => None,
+                        },
+                        _ => None,
+                    };
+
+                    if let Some(color) = color {
+                        if param == 38 {
+                            self.foreground = color;
+                        } else {
+                            self.background = color;
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    fn print(&mut self, character: char) {
+        if self.wrap_pending {
+            self.carriage_return();
+            self.line_feed();
+        }
+
+        let cell = if self.inverse {
+            Cell::new(character, self.background, self.foreground)
+        } else {
+            Cell::new(character, self.foreground, self.background)
+        };
+
+        self.buffer.set(self.get_cursor(), cell);
+
+        if self.x + 1 >= self.buffer.size.width {
+            self.wrap_pending = true;
+        } else {
+            self.x += 1;
+        }
+    }
+
+    #[inline]
+    fn carriage_return(&mut self) {
+        self.x = 0;
+        self.wrap_pending = false;
+    }
+
+    fn line_feed(&mut self) {
+        self.wrap_pending = false;
+
+        if self.y == self.bottom {
+            self.scroll(-1);
+        } else if self.y + 1 < self.buffer.size.height {
+            self.y += 1;
+        }
+    }
+
+    fn reverse_line_feed(&mut self) {
+        self.wrap_pending = false;
+
+        if self.y == self.top {
+            self.scroll(1);
+        } else {
+            self.y = self.y.saturating_sub(1);
+        }
+    }
+
+    fn restore_cursor(&mut self) {
+        self.x = self.saved.0.min(self.buffer.size.width - 1);
+        self.y = self.saved.1.min(self.buffer.size.height - 1);
+        self.wrap_pending = false;
+    }
+
+    /// Scrolls the lines between the margins, down when `rows` is positive.
+    fn scroll(&mut self, rows: i32) {
+        let position = Point2d::new(0, self.top as i32);
+        let size = Size2d::new(self.buffer.size.width, self.bottom + 1 - self.top);
+        let blank = self.blank();
+
+        self.buffer.scroll_region(position, size, 0, rows, blank);
+    }
+
+    /// Inserts (positive) or deletes (negative) lines at the cursor row.
+    fn shift_lines(&mut self, rows: i32) {
+        if self.y < self.top || self.y > self.bottom {
+            return;
+        }
+
+        let position = Point2d::new(0, self.y as i32);
+        let size = Size2d::new(self.buffer.size.width, self.bottom + 1 - self.y);
+        let blank = self.blank();
+
+        self.buffer.scroll_region(position, size, 0, rows, blank);
+        self.x = 0;
+    }
+
+    /// Inserts (positive) or deletes (negative) cells at the cursor column.
+    fn shift_line(&mut self, columns: i32) {
+        let position = self.get_cursor();
+        let size = Size2d::new(self.buffer.size.width - self.x, 1);
+        let blank = self.blank();
+
+        self.buffer.scroll_region(position, size, columns, 0, blank);
+    }
+
+    fn erase_display(&mut self, mode: usize) {
+        let size = self.buffer.size;
+        let blank = self.blank();
+
+        match mode {
+            0 => {
+                self.erase_line(0);
+                self.buffer.fill_rect(
+                    blank,
+                    Point2d::new(0, self.y as i32 + 1),
+                    Size2d::new(size.width, size.height - self.y - 1),
+                );
+            }
+            1 => {
+                self.erase_line(1);
+                self.buffer
+                    .fill_rect(blank, Point2d::empty(), Size2d::new(size.width, self.y));
+            }
+            2 | 3 => self.buffer.fill_rect(blank, Point2d::empty(), size),
+            _ => (),
+        }
+    }
+
+    fn erase_line(&mut self, mode: usize) {
+        let width = self.buffer.size.width;
+        let (start, end) = match mode {
+            0 => (self.x, width),
+            1 => (0, self.x + 1),
+            _ => (0, width),
+        };
+        let blank = self.blank();
+
+        self.buffer.fill_rect(
+            blank,
+            Point2d::new(start as i32, self.y as i32),
+            Size2d::new(end - start, 1),
+        );
+    }
+
+    /// Gets the cell used to erase, keeping the current background.
+    #[inline]
+    fn blank(&self) -> Cell {
+        Cell::new(' ', self.foreground, self.background)
+    }
+}
+
+/// Parses a sequence parameter, ignoring anything but its digits and clamping
+/// it to `MAX_PARAMETER`.
+fn parse_parameter(param: &str) -> usize {
+    param
+        .bytes()
+        .filter(u8::is_ascii_digit)
+        .fold(0, |value, digit| {
+            (value * 10 + (digit - b'0') as usize).min(MAX_PARAMETER)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_screen(width: usize, height: usize, data: &str) -> VtScreen {
+        let mut screen = VtScreen::new(Size2d::new(width, height));
+        screen.feed(data);
+        screen
+    }
+
+    fn get_rows(screen: &VtScreen) -> Vec<String> {
+        let buffer = screen.get_buffer();
+        let characters: Vec<char> = buffer.iter().map(|cell| cell.character).collect();
+
+        characters
+            .chunks(buffer.size.width)
+            .map(|row| row.iter().collect())
+            .collect()
+    }
+
+    #[test]
+    fn text_wraps_at_the_last_column() {
+        let mut screen = get_screen(4, 3, "abcd");
+
+        // the cursor waits on the last column until something is printed.
+        assert_eq!(screen.get_cursor(), Point2d::new(3, 0));
+        assert_eq!(get_rows(&screen), ["abcd", "    ", "    "]);
+
+        screen.feed("ef");
+        assert_eq!(screen.get_cursor(), Point2d::new(2, 1));
+        assert_eq!(get_rows(&screen), ["abcd", "ef  ", "    "]);
+
+        let screen = get_screen(4, 3, "abcd\r\ne");
+        assert_eq!(get_rows(&screen), ["abcd", "e   ", "    "]);
+    }
+
+    #[test]
+    fn wrapping_on_the_last_row_scrolls() {
+        let screen = get_screen(4, 2, "abcdefghij");
+
+        assert_eq!(get_rows(&screen), ["efgh", "ij  "]);
+        assert_eq!(screen.get_cursor(), Point2d::new(2, 1));
+    }
+
+    #[test]
+    fn scroll_region_limits_line_feeds() {
+        let mut screen = get_screen(2, 4, "1\r\n2\r\n3\r\n4");
+
+        screen.feed("\x1b[2;3r");
+        assert_eq!(screen.get_cursor(), Point2d::new(0, 0));
+
+        screen.feed("\x1b[3;1H\n");
+        assert_eq!(get_rows(&screen), ["1 ", "3 ", "  ", "4 "]);
+
+        screen.feed("\x1b[2;1H\x1bM");
+        assert_eq!(get_rows(&screen), ["1 ", "  ", "3 ", "4 "]);
+
+        // regions without at least two lines are ignored.
+        screen.feed("\x1b[4;1H\x1b[3;3r\n");
+        assert_eq!(screen.get_cursor(), Point2d::new(0, 3));
+        assert_eq!(get_rows(&screen), ["1 ", "  ", "3 ", "4 "]);
+    }
+
+    #[test]
+    fn lines_are_inserted_and_deleted_inside_the_region() {
+        let mut screen = get_screen(2, 4, "1\r\n2\r\n3\r\n4\x1b[1;3r\x1b[2;1H");
+
+        screen.feed("\x1b[L");
+        assert_eq!(get_rows(&screen), ["1 ", "  ", "2 ", "4 "]);
+
+        screen.feed("\x1b[2M");
+        assert_eq!(get_rows(&screen), ["1 ", "  ", "  ", "4 "]);
+    }
+
+    #[test]
+    fn erase_line() {
+        let mut screen = get_screen(4, 1, "abcd\x1b[1;3H\x1b[K");
+        assert_eq!(get_rows(&screen), ["ab  "]);
+
+        screen.feed("\x1b[1;1Habcd\x1b[1;2H\x1b[1K");
+        assert_eq!(get_rows(&screen), ["  cd"]);
+
+        screen.feed("\x1b[2K");
+        assert_eq!(get_rows(&screen), ["    "]);
+    }
+
+    #[test]
+    fn erase_display() {
+        let data = "abc\r\ndef\r\nghi\x1b[2;2H";
+
+        assert_eq!(
+            get_rows(&get_screen(3, 3, &format!("{}\x1b[J", data))),
+            ["abc", "d  ", "   "]
+        );
+        assert_eq!(
+            get_rows(&get_screen(3, 3, &format!("{}\x1b[1J", data))),
+            ["   ", "  f", "ghi"]
+        );
+        assert_eq!(
+            get_rows(&get_screen(3, 3, &format!("{}\x1b[2J", data))),
+            ["   ", "   ", "   "]
+        );
+    }
+
+    #[test]
+    fn huge_parameters_are_clamped() {
+        let mut screen = get_screen(4, 3, "\x1b[99999999999999999999;3H");
+        assert_eq!(screen.get_cursor(), Point2d::new(2, 2));
+
+        screen.feed("\x1b[99999999999999999999A\x1b[99999999999999999999C");
+        assert_eq!(screen.get_cursor(), Point2d::new(3, 0));
+
+        screen.feed("\x1b[99999999999999999999B\x1b[99999999999999999999D");
+        assert_eq!(screen.get_cursor(), Point2d::new(0, 2));
+
+        screen.feed("x\x1b[99999999999999999999@\x1b[99999999999999999999L");
+        screen.feed("\x1b[99999999999999999999S\x1b[99999999999999999999X");
+        assert_eq!(get_rows(&screen), ["    ", "    ", "    "]);
+    }
+
+    #[test]
+    fn sequences_with_other_prefixes_are_ignored() {
+        let screen = get_screen(4, 2, "\x1b[2;2H\x1b[>1A\x1b[<u\x1b[=5B");
+
+        assert_eq!(screen.get_cursor(), Point2d::new(1, 1));
+        assert_eq!(get_rows(&screen), ["    ", "    "]);
+    }
+
+    #[test]
+    fn sequences_can_be_split_between_chunks() {
+        let mut screen = get_screen(4, 2, "\x1b[");
+
+        screen.feed("2;");
+        screen.feed("3Hx\x1b]0;title");
+        screen.feed("\x07y");
+
+        assert_eq!(get_rows(&screen), ["    ", "  xy"]);
+    }
+
+    #[test]
+    fn alternate_screen_keeps_the_main_one() {
+        let mut screen = get_screen(3, 1, "abc\x1b[?1049hx");
+        assert_eq!(get_rows(&screen), ["x  "]);
+
+        screen.feed("\x1b[?1049l");
+        assert_eq!(get_rows(&screen), ["abc"]);
+        assert_eq!(screen.get_cursor(), Point2d::new(2, 0));
+    }
+
+    #[test]
+    fn empty_screens_ignore_the_output() {
+        let screen = get_screen(0, 0, "abc\x1b[2J\n");
+
+        assert_eq!(screen.get_buffer().size, Size2d::empty());
+    }
+}
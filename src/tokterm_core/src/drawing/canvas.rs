@@ -2,6 +2,7 @@ use drawing::cell::Cell;
 use drawing::cell_buffer::CellBuffer;
 use drawing::point_2d::Point2d;
 use drawing::size_2d::Size2d;
+use Error;
 use Result;
 
 pub trait Paint {
@@ -64,7 +65,7 @@ impl<'a> Canvas<'a> {
     pub fn line_to(&mut self, position: Point2d) -> Result<()> {
         let stroke = match self.stroke {
             Some(stroke) => stroke,
-            None => return Err("Can not draw a line without a stroke."),
+            None => return Err(Error::InvalidOperation("Can not draw a line without a stroke.")),
         };
 
         let mut x0 = self.position.x;
@@ -101,7 +102,11 @@ impl<'a> Canvas<'a> {
     pub fn bezier_to(&mut self, position: Point2d, control_point: Point2d) -> Result<()> {
         let stroke = match self.stroke {
             Some(stroke) => stroke,
-            None => return Err("Can not draw a bezier line without a stroke."),
+            None => {
+                return Err(Error::InvalidOperation(
+                    "Can not draw a bezier line without a stroke.",
+                ))
+            }
         };
 
         let mut x0 = self.position.x as f64;
@@ -267,7 +272,11 @@ impl<'a> Canvas<'a> {
 
     pub fn stroke_rect(&mut self, position: Point2d, size: Size2d) -> Result<()> {
         match self.stroke {
-            None => return Err("Can not draw a rectangle without a stroke."),
+            None => {
+                return Err(Error::InvalidOperation(
+                    "Can not draw a rectangle without a stroke.",
+                ))
+            }
             _ => (),
         };
 
@@ -288,7 +297,7 @@ impl<'a> Canvas<'a> {
     pub fn fill_rect(&mut self, position: Point2d, size: Size2d) -> Result<()> {
         let fill = match self.fill {
             Some(fill) => fill,
-            None => return Err("Can not fill a rectangle without a fill."),
+            None => return Err(Error::InvalidOperation("Can not fill a rectangle without a fill.")),
         };
 
         let x0 = position.x;
@@ -312,7 +321,7 @@ impl<'a> Canvas<'a> {
     pub fn stroke_circle_from_center(&mut self, center: Point2d, radius: u32) -> Result<()> {
         let stroke = match self.stroke {
             Some(stroke) => stroke,
-            None => return Err("Can not draw a circle without a stroke."),
+            None => return Err(Error::InvalidOperation("Can not draw a circle without a stroke.")),
         };
 
         let cx = center.x;
@@ -351,7 +360,7 @@ impl<'a> Canvas<'a> {
     pub fn fill_circle_from_center(&mut self, center: Point2d, radius: u32) -> Result<()> {
         let fill = match self.fill {
             Some(fill) => fill,
-            None => return Err("Can not fill a circle without a fill."),
+            None => return Err(Error::InvalidOperation("Can not fill a circle without a fill.")),
         };
 
         let cx = center.x;
@@ -393,7 +402,11 @@ impl<'a> Canvas<'a> {
     pub fn stroke_ellipse(&mut self, position: Point2d, size: Size2d) -> Result<()> {
         let stroke = match self.stroke {
             Some(stroke) => stroke,
-            None => return Err("Can not draw an ellipse without a stroke."),
+            None => {
+                return Err(Error::InvalidOperation(
+                    "Can not draw an ellipse without a stroke.",
+                ))
+            }
         };
 
         let mut a = size.width as i32;
@@ -460,7 +473,7 @@ impl<'a> Canvas<'a> {
     pub fn fill_ellipse(&mut self, position: Point2d, size: Size2d) -> Result<()> {
         let fill = match self.fill {
             Some(fill) => fill,
-            None => return Err("Can not fill an ellipse without a fill."),
+            None => return Err(Error::InvalidOperation("Can not fill an ellipse without a fill.")),
         };
 
         let mut a = size.width as i32;
@@ -1,11 +1,12 @@
 use drawing::cell::Cell;
 use drawing::color::Color;
 use drawing::point_2d::Point2d;
+use drawing::rect_2d::Rect2d;
 use drawing::size_2d::Size2d;
 use std::slice::Iter;
 use std::str::Chars;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CellBuffer {
     pub size: Size2d,
     cells: Vec<Cell>,
@@ -133,32 +134,370 @@ impl CellBuffer {
     }
 
     pub fn write_cell_buffer(&mut self, cell_buffer: &CellBuffer, position: Point2d) {
-        let width = cell_buffer.size.width as i32;
-        let height = cell_buffer.size.height as i32;
-        let self_width = self.size.width as i32;
-        let self_height = self.size.height as i32;
+        self.blit_cell_buffer(cell_buffer, Point2d::empty(), cell_buffer.size, position);
+    }
 
-        for cby in 0..height {
-            let destination_y: i32 = position.y + cby;
+    /// Gets the rectangle covering the whole buffer.
+    #[inline]
+    pub fn bounds(&self) -> Rect2d {
+        Rect2d::from(self.size)
+    }
 
-            if destination_y >= self_height {
-                break;
-            }
+    /// Copies a rectangle of another buffer to a destination point, clipping
+    /// the copy on all four sides of both buffers.
+    pub fn blit_cell_buffer(
+        &mut self,
+        cell_buffer: &CellBuffer,
+        position: Point2d,
+        size: Size2d,
+        destination: Point2d,
+    ) {
+        let rect = Rect2d::new(position, size);
+        let (source, destination) =
+            match clip_blit(cell_buffer.bounds(), rect, self.bounds(), destination) {
+                Some(blit) => blit,
+                None => return,
+            };
 
-            for cbx in 0..width {
-                let destination_x: i32 = position.x + cbx;
+        for y in 0..source.size.height {
+            let source_start = cell_buffer.row_start(source.position, y);
+            let destination_start = self.row_start(destination, y);
 
-                if destination_x >= self_width {
-                    break;
-                }
+            self.cells[destination_start..destination_start + source.size.width].copy_from_slice(
+                &cell_buffer.cells[source_start..source_start + source.size.width],
+            );
+        }
+    }
+
+    /// Fills a rectangle with the given cell, clipping it to the buffer bounds.
+    pub fn fill_rect(&mut self, cell: Cell, position: Point2d, size: Size2d) {
+        let rect = match self.bounds().intersection(Rect2d::new(position, size)) {
+            Some(rect) => rect,
+            None => return,
+        };
 
-                let cell = match cell_buffer.get(Point2d::new(cbx, cby)) {
-                    Some(cell) => cell,
-                    None => return,
-                };
+        for y in 0..rect.size.height {
+            let start = self.row_start(rect.position, y);
 
-                self.set(Point2d::new(destination_x, destination_y), cell);
+            for target in &mut self.cells[start..start + rect.size.width] {
+                *target = cell;
             }
         }
     }
+
+    /// Copies a rectangle of cells to another position of the same buffer.
+    /// The source and the destination can overlap.
+    pub fn copy_rect(&mut self, position: Point2d, size: Size2d, destination: Point2d) {
+        let rect = Rect2d::new(position, size);
+        let (source, destination) = match clip_blit(self.bounds(), rect, self.bounds(), destination)
+        {
+            Some(blit) => blit,
+            None => return,
+        };
+
+        let width = source.size.width;
+
+        // when moving down, rows must be copied from the bottom to avoid
+        // overwriting the source before it is read.
+        if destination.y > source.position.y {
+            for y in (0..source.size.height).rev() {
+                let start = self.row_start(source.position, y);
+                let target = self.row_start(destination, y);
+                self.cells.copy_within(start..start + width, target);
+            }
+        } else {
+            for y in 0..source.size.height {
+                let start = self.row_start(source.position, y);
+                let target = self.row_start(destination, y);
+                self.cells.copy_within(start..start + width, target);
+            }
+        }
+    }
+
+    /// Shifts the cells inside a region by `dx` columns and `dy` rows,
+    /// filling the uncovered cells with `fill`.
+    pub fn scroll_region(&mut self, position: Point2d, size: Size2d, dx: i32, dy: i32, fill: Cell) {
+        let rect = match self.bounds().intersection(Rect2d::new(position, size)) {
+            Some(rect) => rect,
+            None => return,
+        };
+
+        // `unsigned_abs` since the absolute value of `i32::MIN` overflows.
+        let columns = dx.unsigned_abs() as usize;
+        let rows = dy.unsigned_abs() as usize;
+
+        if columns >= rect.size.width || rows >= rect.size.height {
+            self.fill_rect(fill, rect.position, rect.size);
+            return;
+        }
+
+        self.copy_rect(
+            rect.position + Point2d::new((-dx).max(0), (-dy).max(0)),
+            rect.size - Size2d::new(columns, rows),
+            rect.position + Point2d::new(dx.max(0), dy.max(0)),
+        );
+
+        let (top, rest) = rect.split_vertical(dy.max(0) as usize);
+        let (_, bottom) = rest.split_vertical(rect.size.height - rows);
+        let (left, rest) = rect.split_horizontal(dx.max(0) as usize);
+        let (_, right) = rest.split_horizontal(rect.size.width - columns);
+
+        for uncovered in &[top, bottom, left, right] {
+            self.fill_rect(fill, uncovered.position, uncovered.size);
+        }
+    }
+
+    #[inline]
+    fn row_start(&self, position: Point2d, row: usize) -> usize {
+        position.x as usize + (position.y as usize + row) * self.size.width
+    }
+}
+
+/// Clips a copy of a source rectangle onto a destination point, so that both
+/// the read and the written cells are inside their bounds. Returns the
+/// adjusted source rectangle and destination point.
+fn clip_blit(
+    source_bounds: Rect2d,
+    rect: Rect2d,
+    destination_bounds: Rect2d,
+    destination: Point2d,
+) -> Option<(Rect2d, Point2d)> {
+    let source = source_bounds.intersection(rect)?;
+    let offset = destination - rect.position;
+    let target = destination_bounds.intersection(source + offset)?;
+
+    Some((target - offset, target.position))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_buffer(rows: &[&str]) -> CellBuffer {
+        let size = Size2d::new(rows[0].len(), rows.len());
+        let mut cell_buffer = CellBuffer::new(Cell::default(' '), size);
+
+        for (y, row) in rows.iter().enumerate() {
+            cell_buffer.write_str(row, Point2d::new(0, y as i32), Color::Grey, Color::Black);
+        }
+
+        cell_buffer
+    }
+
+    fn get_rows(cell_buffer: &CellBuffer) -> Vec<String> {
+        cell_buffer
+            .cells
+            .chunks(cell_buffer.size.width)
+            .map(|row| row.iter().map(|cell| cell.character).collect())
+            .collect()
+    }
+
+    #[test]
+    fn fill_rect_clips_negative_positions() {
+        let mut cell_buffer = get_buffer(&["....", "....", "...."]);
+        cell_buffer.fill_rect(Cell::default('#'), Point2d::new(-2, -1), Size2d::new(3, 2));
+
+        assert_eq!(get_rows(&cell_buffer), ["#...", "....", "...."]);
+    }
+
+    #[test]
+    fn fill_rect_clips_out_of_bounds_sizes() {
+        let mut cell_buffer = get_buffer(&["....", "...."]);
+        cell_buffer.fill_rect(
+            Cell::default('#'),
+            Point2d::new(2, 1),
+            Size2d::new(100, 100),
+        );
+
+        assert_eq!(get_rows(&cell_buffer), ["....", "..##"]);
+    }
+
+    #[test]
+    fn fill_rect_outside_the_buffer_does_nothing() {
+        let mut cell_buffer = get_buffer(&["....", "...."]);
+        cell_buffer.fill_rect(Cell::default('#'), Point2d::new(4, 0), Size2d::new(2, 2));
+        cell_buffer.fill_rect(Cell::default('#'), Point2d::new(-3, 0), Size2d::new(3, 2));
+        cell_buffer.fill_rect(Cell::default('#'), Point2d::new(0, 0), Size2d::new(0, 2));
+
+        assert_eq!(get_rows(&cell_buffer), ["....", "...."]);
+    }
+
+    #[test]
+    fn copy_rect_handles_overlapping_copies() {
+        let mut cell_buffer = get_buffer(&["ab..", "cd..", "...."]);
+        cell_buffer.copy_rect(Point2d::new(0, 0), Size2d::new(2, 2), Point2d::new(1, 1));
+
+        assert_eq!(get_rows(&cell_buffer), ["ab..", "cab.", ".cd."]);
+
+        cell_buffer.copy_rect(Point2d::new(1, 1), Size2d::new(2, 2), Point2d::new(0, 0));
+
+        assert_eq!(get_rows(&cell_buffer), ["ab..", "cdb.", ".cd."]);
+    }
+
+    #[test]
+    fn copy_rect_clips_the_destination() {
+        let mut cell_buffer = get_buffer(&["ab..", "...."]);
+        cell_buffer.copy_rect(Point2d::new(0, 0), Size2d::new(2, 1), Point2d::new(3, 1));
+        cell_buffer.copy_rect(Point2d::new(0, 0), Size2d::new(2, 1), Point2d::new(-1, 1));
+
+        assert_eq!(get_rows(&cell_buffer), ["ab..", "b..a"]);
+    }
+
+    #[test]
+    fn copy_rect_clips_the_source() {
+        let mut cell_buffer = get_buffer(&["ab..", "...."]);
+        cell_buffer.copy_rect(Point2d::new(-1, 0), Size2d::new(2, 1), Point2d::new(2, 1));
+
+        assert_eq!(get_rows(&cell_buffer), ["ab..", "...a"]);
+    }
+
+    #[test]
+    fn scroll_region_shifts_and_fills() {
+        let mut cell_buffer = get_buffer(&["aaa", "bbb", "ccc"]);
+        cell_buffer.scroll_region(
+            Point2d::empty(),
+            Size2d::new(3, 3),
+            0,
+            -1,
+            Cell::default('#'),
+        );
+
+        assert_eq!(get_rows(&cell_buffer), ["bbb", "ccc", "###"]);
+
+        cell_buffer.scroll_region(
+            Point2d::new(1, 0),
+            Size2d::new(2, 2),
+            1,
+            1,
+            Cell::default('.'),
+        );
+
+        assert_eq!(get_rows(&cell_buffer), ["b..", "c.b", "###"]);
+    }
+
+    #[test]
+    fn scroll_region_clips_to_the_buffer() {
+        let mut cell_buffer = get_buffer(&["abc", "def"]);
+        cell_buffer.scroll_region(
+            Point2d::new(-1, -1),
+            Size2d::new(3, 3),
+            -1,
+            0,
+            Cell::default('#'),
+        );
+
+        assert_eq!(get_rows(&cell_buffer), ["b#c", "e#f"]);
+    }
+
+    #[test]
+    fn scroll_offsets_larger_than_the_region_clear_it() {
+        for &(dx, dy) in &[
+            (0, 3),
+            (0, -3),
+            (5, 0),
+            (-100, 1),
+            (i32::MIN, 0),
+            (0, i32::MAX),
+        ] {
+            let mut cell_buffer = get_buffer(&["abc", "def", "ghi"]);
+            cell_buffer.scroll_region(
+                Point2d::new(1, 0),
+                Size2d::new(2, 3),
+                dx,
+                dy,
+                Cell::default('#'),
+            );
+
+            assert_eq!(get_rows(&cell_buffer), ["a##", "d##", "g##"]);
+        }
+    }
+
+    #[test]
+    fn scroll_region_outside_the_buffer_does_nothing() {
+        let mut cell_buffer = get_buffer(&["abc"]);
+        cell_buffer.scroll_region(
+            Point2d::new(3, 0),
+            Size2d::new(2, 1),
+            1,
+            0,
+            Cell::default('#'),
+        );
+        cell_buffer.scroll_region(
+            Point2d::new(0, -1),
+            Size2d::new(3, 1),
+            1,
+            0,
+            Cell::default('#'),
+        );
+
+        assert_eq!(get_rows(&cell_buffer), ["abc"]);
+    }
+
+    #[test]
+    fn blit_cell_buffer_copies_a_source_rect() {
+        let source = get_buffer(&["abc", "def"]);
+        let mut cell_buffer = get_buffer(&["....", "....", "...."]);
+        cell_buffer.blit_cell_buffer(
+            &source,
+            Point2d::new(1, 0),
+            Size2d::new(2, 2),
+            Point2d::new(2, 1),
+        );
+
+        assert_eq!(get_rows(&cell_buffer), ["....", "..bc", "..ef"]);
+    }
+
+    #[test]
+    fn blit_cell_buffer_clips_negative_destinations() {
+        let source = get_buffer(&["abc", "def"]);
+        let mut cell_buffer = get_buffer(&["...", "..."]);
+        cell_buffer.blit_cell_buffer(&source, Point2d::empty(), source.size, Point2d::new(-1, -1));
+
+        assert_eq!(get_rows(&cell_buffer), ["ef.", "..."]);
+    }
+
+    #[test]
+    fn blit_cell_buffer_clips_the_source_rect() {
+        let source = get_buffer(&["abc", "def"]);
+        let mut cell_buffer = get_buffer(&["...", "..."]);
+        cell_buffer.blit_cell_buffer(
+            &source,
+            Point2d::new(-1, 1),
+            Size2d::new(10, 10),
+            Point2d::empty(),
+        );
+
+        assert_eq!(get_rows(&cell_buffer), [".de", "..."]);
+    }
+
+    #[test]
+    fn blit_cell_buffer_outside_the_bounds_does_nothing() {
+        let source = get_buffer(&["abc", "def"]);
+        let mut cell_buffer = get_buffer(&["...", "..."]);
+        cell_buffer.blit_cell_buffer(
+            &source,
+            Point2d::new(3, 0),
+            Size2d::new(2, 2),
+            Point2d::empty(),
+        );
+        cell_buffer.blit_cell_buffer(&source, Point2d::empty(), source.size, Point2d::new(3, 0));
+        cell_buffer.blit_cell_buffer(&source, Point2d::empty(), source.size, Point2d::new(-3, 0));
+
+        assert_eq!(get_rows(&cell_buffer), ["...", "..."]);
+    }
+
+    #[test]
+    fn clip_blit_adjusts_the_source_and_destination() {
+        let bounds = Rect2d::from(Size2d::new(4, 4));
+        let rect = Rect2d::new(Point2d::new(-1, 0), Size2d::new(3, 3));
+
+        assert_eq!(
+            clip_blit(bounds, rect, bounds, Point2d::new(2, -1)),
+            Some((
+                Rect2d::new(Point2d::new(0, 1), Size2d::new(1, 2)),
+                Point2d::new(3, 0)
+            ))
+        );
+        assert_eq!(clip_blit(bounds, rect, bounds, Point2d::new(5, 0)), None);
+    }
 }
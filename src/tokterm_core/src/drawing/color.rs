@@ -1,3 +1,4 @@
+use drawing::rgb::Rgb;
 use std::vec::Vec;
 
 // TODO: remove allow dead code.
@@ -45,8 +46,103 @@ impl Color {
         vec.push(Color::DarkMagenta);
         vec.push(Color::DarkCyan);
 
-
         vec.push(Color::White);
         vec
     }
+
+    /// Gets the RGB value of the color in the classic VGA console palette.
+    pub fn to_rgb(&self) -> Rgb {
+        Rgb::from_u32(match self {
+            Color::Black => 0x000000,
+            Color::DarkRed => 0x800000,
+            Color::DarkGreen => 0x008000,
+            Color::DarkYellow => 0x808000,
+            Color::DarkBlue => 0x000080,
+            Color::DarkMagenta => 0x800080,
+            Color::DarkCyan => 0x008080,
+            Color::Grey => 0xC0C0C0,
+            Color::DarkGrey => 0x808080,
+            Color::Red => 0xFF0000,
+            Color::Green => 0x00FF00,
+            Color::Yellow => 0xFFFF00,
+            Color::Blue => 0x0000FF,
+            Color::Magenta => 0xFF00FF,
+            Color::Cyan => 0x00FFFF,
+            Color::White => 0xFFFFFF,
+        })
+    }
+
+    /// Gets the base color perceptually nearest to an RGB value.
+    pub fn from_rgb(rgb: Rgb) -> Color {
+        nearest(
+            rgb,
+            Color::to_vec()
+                .into_iter()
+                .map(|color| (color, color.to_rgb())),
+        )
+    }
+
+    /// Parses a hex string or CSS color name into the nearest base color.
+    pub fn parse(text: &str) -> Option<Color> {
+        Rgb::parse(text).map(Color::from_rgb)
+    }
+}
+
+/// Gets the color of the candidate perceptually nearest to an RGB value.
+pub fn nearest<I>(rgb: Rgb, candidates: I) -> Color
+where
+    I: IntoIterator<Item = (Color, Rgb)>,
+{
+    let mut nearest_color = Color::Black;
+    let mut nearest_distance = f64::MAX;
+
+    for (color, candidate) in candidates {
+        let distance = rgb.distance(candidate);
+
+        if distance < nearest_distance {
+            nearest_color = color;
+            nearest_distance = distance;
+        }
+    }
+
+    nearest_color
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base_colors_match_themselves() {
+        for color in Color::to_vec() {
+            assert_eq!(Color::from_rgb(color.to_rgb()), color);
+        }
+    }
+
+    #[test]
+    fn nearest_base_colors() {
+        assert_eq!(Color::from_rgb(Rgb::from_u32(0xF01010)), Color::Red);
+        assert_eq!(Color::from_rgb(Rgb::from_u32(0x700000)), Color::DarkRed);
+        assert_eq!(Color::from_rgb(Rgb::from_u32(0xC8C8C8)), Color::Grey);
+        assert_eq!(Color::from_rgb(Rgb::from_u32(0x101010)), Color::Black);
+        assert_eq!(Color::from_rgb(Rgb::from_u32(0x0000A0)), Color::DarkBlue);
+    }
+
+    #[test]
+    fn parse_picks_the_nearest_color() {
+        assert_eq!(Color::parse("#fff"), Some(Color::White));
+        assert_eq!(Color::parse("navy"), Some(Color::DarkBlue));
+        assert_eq!(Color::parse("nothing"), None);
+    }
+
+    #[test]
+    fn nearest_picks_among_the_candidates() {
+        let candidates = vec![
+            (Color::Blue, Rgb::new(0, 0, 255)),
+            (Color::Green, Rgb::new(0, 255, 0)),
+        ];
+
+        assert_eq!(nearest(Rgb::new(255, 0, 0), vec![]), Color::Black);
+        assert_eq!(nearest(Rgb::new(40, 200, 40), candidates), Color::Green);
+    }
 }
@@ -0,0 +1,19 @@
+/// A color in the HSL space, with the hue in degrees and the saturation and
+/// lightness between 0 and 1.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Hsl {
+    pub hue: f64,
+    pub saturation: f64,
+    pub lightness: f64,
+}
+
+#[allow(dead_code)]
+impl Hsl {
+    pub fn new(hue: f64, saturation: f64, lightness: f64) -> Hsl {
+        Hsl {
+            hue,
+            saturation,
+            lightness,
+        }
+    }
+}
@@ -0,0 +1,19 @@
+/// A color in the HSV space, with the hue in degrees and the saturation and
+/// value between 0 and 1.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Hsv {
+    pub hue: f64,
+    pub saturation: f64,
+    pub value: f64,
+}
+
+#[allow(dead_code)]
+impl Hsv {
+    pub fn new(hue: f64, saturation: f64, value: f64) -> Hsv {
+        Hsv {
+            hue,
+            saturation,
+            value,
+        }
+    }
+}
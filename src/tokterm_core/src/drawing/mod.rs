@@ -2,5 +2,11 @@ pub mod canvas;
 pub mod cell;
 pub mod cell_buffer;
 pub mod color;
+pub mod hsl;
+pub mod hsv;
+pub mod palette;
 pub mod point_2d;
+pub mod rect_2d;
+pub mod rgb;
+pub mod sgr;
 pub mod size_2d;
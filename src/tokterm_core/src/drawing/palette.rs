@@ -0,0 +1,184 @@
+use drawing::color::{nearest, Color};
+use drawing::rgb::Rgb;
+use std::collections::HashMap;
+
+/// The base colors in the order used by the ANSI color indices 0 to 15.
+const ANSI_COLORS: [Color; 16] = [
+    Color::Black,
+    Color::DarkRed,
+    Color::DarkGreen,
+    Color::DarkYellow,
+    Color::DarkBlue,
+    Color::DarkMagenta,
+    Color::DarkCyan,
+    Color::Grey,
+    Color::DarkGrey,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::White,
+];
+
+/// Maps the base colors to concrete RGB values, and named roles like
+/// `"accent"` or `"error"` to base colors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Palette {
+    colors: [Rgb; 16],
+    roles: HashMap<String, Color>,
+}
+
+impl Default for Palette {
+    /// The classic VGA console colors, as given by `Color::to_rgb`.
+    fn default() -> Palette {
+        let mut values = [0; 16];
+
+        for (index, color) in ANSI_COLORS.iter().enumerate() {
+            values[index] = color.to_rgb().to_u32();
+        }
+
+        Palette::from_ansi(values)
+    }
+}
+
+#[allow(dead_code)]
+impl Palette {
+    /// Creates a palette from sixteen `0xRRGGBB` values in ANSI order.
+    pub fn from_ansi(values: [u32; 16]) -> Palette {
+        let mut colors = [Rgb::new(0, 0, 0); 16];
+
+        for (index, value) in values.iter().enumerate() {
+            colors[ANSI_COLORS[index] as usize] = Rgb::from_u32(*value);
+        }
+
+        let mut palette = Palette {
+            colors,
+            roles: HashMap::new(),
+        };
+
+        palette.set_role("foreground", Color::Grey);
+        palette.set_role("background", Color::Black);
+        palette.set_role("muted", Color::DarkGrey);
+        palette.set_role("accent", Color::Blue);
+        palette.set_role("selection", Color::DarkBlue);
+        palette.set_role("success", Color::Green);
+        palette.set_role("warning", Color::Yellow);
+        palette.set_role("error", Color::Red);
+        palette
+    }
+
+    pub fn solarized_dark() -> Palette {
+        let mut palette = Palette::from_ansi([
+            0x073642, 0xDC322F, 0x859900, 0xB58900, 0x268BD2, 0xD33682, 0x2AA198, 0x839496,
+            0x586E75, 0xCB4B16, 0x93A1A1, 0xEEE8D5, 0x6C71C4, 0xD33682, 0x2AA198, 0xFDF6E3,
+        ]);
+        palette.set_role("accent", Color::DarkBlue);
+        palette.set_role("success", Color::DarkGreen);
+        palette.set_role("warning", Color::DarkYellow);
+        palette.set_role("error", Color::DarkRed);
+        palette
+    }
+
+    pub fn solarized_light() -> Palette {
+        let mut palette = Palette::from_ansi([
+            0xFDF6E3, 0xDC322F, 0x859900, 0xB58900, 0x268BD2, 0xD33682, 0x2AA198, 0x657B83,
+            0xEEE8D5, 0xCB4B16, 0x586E75, 0x073642, 0x6C71C4, 0xD33682, 0x2AA198, 0x002B36,
+        ]);
+        palette.set_role("accent", Color::DarkBlue);
+        palette.set_role("success", Color::DarkGreen);
+        palette.set_role("warning", Color::DarkYellow);
+        palette.set_role("error", Color::DarkRed);
+        palette
+    }
+
+    pub fn gruvbox_dark() -> Palette {
+        Palette::from_ansi([
+            0x282828, 0xCC241D, 0x98971A, 0xD79921, 0x458588, 0xB16286, 0x689D6A, 0xA89984,
+            0x928374, 0xFB4934, 0xB8BB26, 0xFABD2F, 0x83A598, 0xD3869B, 0x8EC07C, 0xEBDBB2,
+        ])
+    }
+
+    pub fn gruvbox_light() -> Palette {
+        Palette::from_ansi([
+            0xFBF1C7, 0xCC241D, 0x98971A, 0xD79921, 0x458588, 0xB16286, 0x689D6A, 0x7C6F64,
+            0x928374, 0x9D0006, 0x79740E, 0xB57614, 0x076678, 0x8F3F71, 0x427B58, 0x3C3836,
+        ])
+    }
+
+    pub fn high_contrast() -> Palette {
+        let mut palette = Palette::from_ansi([
+            0x000000, 0xFF2020, 0x20FF20, 0xFFFF00, 0x2080FF, 0xFF20FF, 0x00FFFF, 0xFFFFFF,
+            0xA0A0A0, 0xFF6060, 0x80FF80, 0xFFFF80, 0x80C0FF, 0xFF80FF, 0x80FFFF, 0xFFFFFF,
+        ]);
+        palette.set_role("muted", Color::Grey);
+        palette.set_role("selection", Color::Blue);
+        palette
+    }
+
+    /// A palette based on the Okabe-Ito colors, distinguishable with the most
+    /// common forms of color blindness.
+    pub fn color_blind() -> Palette {
+        Palette::from_ansi([
+            0x000000, 0xD55E00, 0x009E73, 0xE69F00, 0x0072B2, 0xCC79A7, 0x56B4E9, 0xC0C0C0,
+            0x808080, 0xFF7F2A, 0x2DD4A0, 0xF0E442, 0x3A9BE0, 0xE6A3C8, 0x8FD0F5, 0xFFFFFF,
+        ])
+    }
+
+    /// Gets one of the bundled themes by name.
+    pub fn theme(name: &str) -> Option<Palette> {
+        match name {
+            "default" => Some(Palette::default()),
+            "solarized-dark" => Some(Palette::solarized_dark()),
+            "solarized-light" => Some(Palette::solarized_light()),
+            "gruvbox-dark" => Some(Palette::gruvbox_dark()),
+            "gruvbox-light" => Some(Palette::gruvbox_light()),
+            "high-contrast" => Some(Palette::high_contrast()),
+            "color-blind" => Some(Palette::color_blind()),
+            _ => None,
+        }
+    }
+
+    /// Lists the names accepted by `Palette::theme`.
+    pub fn theme_names() -> Vec<&'static str> {
+        vec![
+            "default",
+            "solarized-dark",
+            "solarized-light",
+            "gruvbox-dark",
+            "gruvbox-light",
+            "high-contrast",
+            "color-blind",
+        ]
+    }
+
+    #[inline]
+    pub fn get(&self, color: Color) -> Rgb {
+        self.colors[color as usize]
+    }
+
+    #[inline]
+    pub fn set(&mut self, color: Color, rgb: Rgb) {
+        self.colors[color as usize] = rgb;
+    }
+
+    /// Gets the base color whose value in this palette is perceptually
+    /// nearest to an RGB value.
+    pub fn nearest(&self, rgb: Rgb) -> Color {
+        nearest(
+            rgb,
+            ANSI_COLORS.iter().map(|color| (*color, self.get(*color))),
+        )
+    }
+
+    /// Resolves a named role to the base color assigned to it.
+    #[inline]
+    pub fn get_role(&self, role: &str) -> Option<Color> {
+        self.roles.get(role).cloned()
+    }
+
+    pub fn set_role(&mut self, role: &str, color: Color) {
+        self.roles.insert(role.to_string(), color);
+    }
+}
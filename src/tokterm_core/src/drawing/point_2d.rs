@@ -1,3 +1,6 @@
+use drawing::size_2d::Size2d;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
 #[derive(Hash, Debug, Copy, Clone, PartialEq, Eq)]
 pub struct Point2d {
     pub x: i32,
@@ -34,3 +37,67 @@ impl Point2d {
         self.x == 0 && self.y == 0
     }
 }
+
+impl Add for Point2d {
+    type Output = Point2d;
+
+    fn add(self, point: Point2d) -> Point2d {
+        Point2d::new(self.x + point.x, self.y + point.y)
+    }
+}
+
+impl Sub for Point2d {
+    type Output = Point2d;
+
+    fn sub(self, point: Point2d) -> Point2d {
+        Point2d::new(self.x - point.x, self.y - point.y)
+    }
+}
+
+impl AddAssign for Point2d {
+    fn add_assign(&mut self, point: Point2d) {
+        self.x += point.x;
+        self.y += point.y;
+    }
+}
+
+impl SubAssign for Point2d {
+    fn sub_assign(&mut self, point: Point2d) {
+        self.x -= point.x;
+        self.y -= point.y;
+    }
+}
+
+impl Mul<i32> for Point2d {
+    type Output = Point2d;
+
+    fn mul(self, factor: i32) -> Point2d {
+        Point2d::new(self.x * factor, self.y * factor)
+    }
+}
+
+impl Neg for Point2d {
+    type Output = Point2d;
+
+    fn neg(self) -> Point2d {
+        Point2d::new(-self.x, -self.y)
+    }
+}
+
+impl From<(i32, i32)> for Point2d {
+    fn from((x, y): (i32, i32)) -> Point2d {
+        Point2d::new(x, y)
+    }
+}
+
+impl From<Point2d> for (i32, i32) {
+    fn from(point: Point2d) -> (i32, i32) {
+        (point.x, point.y)
+    }
+}
+
+impl From<Size2d> for Point2d {
+    fn from(size: Size2d) -> Point2d {
+        Point2d::new(size.width as i32, size.height as i32)
+    }
+}
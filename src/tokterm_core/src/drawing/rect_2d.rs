@@ -0,0 +1,311 @@
+use drawing::point_2d::Point2d;
+use drawing::size_2d::Size2d;
+use std::ops::{Add, Sub};
+
+#[derive(Hash, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Rect2d {
+    pub position: Point2d,
+    pub size: Size2d,
+}
+
+#[allow(dead_code)]
+impl Rect2d {
+    pub fn new(position: Point2d, size: Size2d) -> Rect2d {
+        Rect2d { position, size }
+    }
+
+    pub fn empty() -> Rect2d {
+        Rect2d {
+            position: Point2d::empty(),
+            size: Size2d::empty(),
+        }
+    }
+
+    /// Creates a rectangle from its top-left corner (inclusive) and its
+    /// bottom-right corner (exclusive).
+    pub fn from_corners(top_left: Point2d, bottom_right: Point2d) -> Rect2d {
+        Rect2d::new(
+            top_left,
+            Size2d::new(
+                (bottom_right.x - top_left.x).max(0) as usize,
+                (bottom_right.y - top_left.y).max(0) as usize,
+            ),
+        )
+    }
+
+    #[inline]
+    pub fn left(&self) -> i32 {
+        self.position.x
+    }
+
+    #[inline]
+    pub fn top(&self) -> i32 {
+        self.position.y
+    }
+
+    /// Gets the first column to the right of the rectangle.
+    #[inline]
+    pub fn right(&self) -> i32 {
+        self.position.x + self.size.width as i32
+    }
+
+    /// Gets the first row below the rectangle.
+    #[inline]
+    pub fn bottom(&self) -> i32 {
+        self.position.y + self.size.height as i32
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.size.width == 0 || self.size.height == 0
+    }
+
+    pub fn contains(&self, point: Point2d) -> bool {
+        point.x >= self.left()
+            && point.y >= self.top()
+            && point.x < self.right()
+            && point.y < self.bottom()
+    }
+
+    pub fn contains_rect(&self, rect: Rect2d) -> bool {
+        rect.left() >= self.left()
+            && rect.top() >= self.top()
+            && rect.right() <= self.right()
+            && rect.bottom() <= self.bottom()
+    }
+
+    pub fn intersects(&self, rect: Rect2d) -> bool {
+        self.intersection(rect).is_some()
+    }
+
+    /// Gets the area shared by both rectangles, if there is any.
+    pub fn intersection(&self, rect: Rect2d) -> Option<Rect2d> {
+        let result = Rect2d::from_corners(
+            Point2d::new(self.left().max(rect.left()), self.top().max(rect.top())),
+            Point2d::new(
+                self.right().min(rect.right()),
+                self.bottom().min(rect.bottom()),
+            ),
+        );
+
+        if result.is_empty() {
+            return None;
+        }
+
+        Some(result)
+    }
+
+    /// Gets the smallest rectangle containing both rectangles.
+    pub fn union(&self, rect: Rect2d) -> Rect2d {
+        if self.is_empty() {
+            return rect;
+        }
+
+        if rect.is_empty() {
+            return *self;
+        }
+
+        Rect2d::from_corners(
+            Point2d::new(self.left().min(rect.left()), self.top().min(rect.top())),
+            Point2d::new(
+                self.right().max(rect.right()),
+                self.bottom().max(rect.bottom()),
+            ),
+        )
+    }
+
+    /// Shrinks the rectangle by `amount` cells on every side.
+    pub fn inset(&self, amount: i32) -> Rect2d {
+        Rect2d::from_corners(
+            self.position.add(Point2d::new(amount, amount)),
+            Point2d::new(self.right() - amount, self.bottom() - amount),
+        )
+    }
+
+    /// Grows the rectangle by `amount` cells on every side.
+    pub fn outset(&self, amount: i32) -> Rect2d {
+        self.inset(-amount)
+    }
+
+    /// Splits the rectangle into a left part `width` columns wide and a
+    /// right part with the rest.
+    pub fn split_horizontal(&self, width: usize) -> (Rect2d, Rect2d) {
+        let width = width.min(self.size.width);
+
+        (
+            Rect2d::new(self.position, Size2d::new(width, self.size.height)),
+            Rect2d::new(
+                self.position.add_x(width as i32),
+                Size2d::new(self.size.width - width, self.size.height),
+            ),
+        )
+    }
+
+    /// Splits the rectangle into a top part `height` rows tall and a bottom
+    /// part with the rest.
+    pub fn split_vertical(&self, height: usize) -> (Rect2d, Rect2d) {
+        let height = height.min(self.size.height);
+
+        (
+            Rect2d::new(self.position, Size2d::new(self.size.width, height)),
+            Rect2d::new(
+                self.position.add_y(height as i32),
+                Size2d::new(self.size.width, self.size.height - height),
+            ),
+        )
+    }
+
+    /// Iterates over the points of the rectangle, row by row.
+    pub fn iter(&self) -> Rect2dIter {
+        Rect2dIter {
+            rect: *self,
+            next: self.position,
+        }
+    }
+}
+
+impl Add<Point2d> for Rect2d {
+    type Output = Rect2d;
+
+    fn add(self, offset: Point2d) -> Rect2d {
+        Rect2d::new(self.position + offset, self.size)
+    }
+}
+
+impl Sub<Point2d> for Rect2d {
+    type Output = Rect2d;
+
+    fn sub(self, offset: Point2d) -> Rect2d {
+        Rect2d::new(self.position - offset, self.size)
+    }
+}
+
+impl From<(Point2d, Size2d)> for Rect2d {
+    fn from((position, size): (Point2d, Size2d)) -> Rect2d {
+        Rect2d::new(position, size)
+    }
+}
+
+impl From<Size2d> for Rect2d {
+    fn from(size: Size2d) -> Rect2d {
+        Rect2d::new(Point2d::empty(), size)
+    }
+}
+
+impl IntoIterator for Rect2d {
+    type Item = Point2d;
+    type IntoIter = Rect2dIter;
+
+    fn into_iter(self) -> Rect2dIter {
+        self.iter()
+    }
+}
+
+pub struct Rect2dIter {
+    rect: Rect2d,
+    next: Point2d,
+}
+
+impl Iterator for Rect2dIter {
+    type Item = Point2d;
+
+    fn next(&mut self) -> Option<Point2d> {
+        if self.rect.is_empty() || self.next.y >= self.rect.bottom() {
+            return None;
+        }
+
+        let point = self.next;
+        self.next.x += 1;
+
+        if self.next.x >= self.rect.right() {
+            self.next.x = self.rect.left();
+            self.next.y += 1;
+        }
+
+        Some(point)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: i32, y: i32, width: usize, height: usize) -> Rect2d {
+        Rect2d::new(Point2d::new(x, y), Size2d::new(width, height))
+    }
+
+    #[test]
+    fn intersection_of_overlapping_rects() {
+        assert_eq!(
+            rect(0, 0, 4, 3).intersection(rect(2, 1, 5, 5)),
+            Some(rect(2, 1, 2, 2))
+        );
+        assert_eq!(
+            rect(-3, -2, 5, 4).intersection(rect(0, 0, 10, 10)),
+            Some(rect(0, 0, 2, 2))
+        );
+    }
+
+    #[test]
+    fn intersection_of_disjoint_rects_is_empty() {
+        // touching edges share no cell.
+        assert_eq!(rect(0, 0, 2, 2).intersection(rect(2, 0, 2, 2)), None);
+        assert_eq!(rect(0, 0, 2, 2).intersection(rect(0, -2, 2, 2)), None);
+        assert_eq!(rect(0, 0, 2, 2).intersection(rect(5, 5, 1, 1)), None);
+        assert_eq!(rect(0, 0, 2, 2).intersection(rect(1, 1, 0, 3)), None);
+        assert!(!rect(0, 0, 2, 2).intersects(rect(-1, -1, 1, 1)));
+    }
+
+    #[test]
+    fn union_skips_empty_rects() {
+        assert_eq!(rect(0, 0, 2, 2).union(rect(3, -1, 1, 1)), rect(0, -1, 4, 3));
+        assert_eq!(rect(1, 1, 2, 2).union(rect(-5, -5, 0, 0)), rect(1, 1, 2, 2));
+        assert_eq!(rect(9, 9, 0, 3).union(rect(1, 1, 2, 2)), rect(1, 1, 2, 2));
+    }
+
+    #[test]
+    fn split_clamps_to_the_size() {
+        assert_eq!(
+            rect(1, 2, 4, 3).split_horizontal(1),
+            (rect(1, 2, 1, 3), rect(2, 2, 3, 3))
+        );
+        assert_eq!(
+            rect(1, 2, 4, 3).split_vertical(10),
+            (rect(1, 2, 4, 3), rect(1, 5, 4, 0))
+        );
+    }
+
+    #[test]
+    fn inset_and_outset() {
+        assert_eq!(rect(0, 0, 5, 4).inset(1), rect(1, 1, 3, 2));
+        assert_eq!(rect(0, 0, 5, 4).outset(1), rect(-1, -1, 7, 6));
+        assert!(rect(0, 0, 2, 2).inset(3).is_empty());
+    }
+
+    #[test]
+    fn contains_excludes_the_far_edges() {
+        let bounds = rect(-1, -1, 2, 2);
+
+        assert!(bounds.contains(Point2d::new(-1, -1)));
+        assert!(bounds.contains(Point2d::new(0, 0)));
+        assert!(!bounds.contains(Point2d::new(1, 0)));
+        assert!(bounds.contains_rect(rect(-1, 0, 2, 1)));
+        assert!(!bounds.contains_rect(rect(-2, 0, 2, 1)));
+    }
+
+    #[test]
+    fn iter_walks_row_by_row() {
+        let points: Vec<_> = rect(-1, 0, 2, 2).iter().collect();
+
+        assert_eq!(
+            points,
+            [
+                Point2d::new(-1, 0),
+                Point2d::new(0, 0),
+                Point2d::new(-1, 1),
+                Point2d::new(0, 1)
+            ]
+        );
+        assert_eq!(rect(0, 0, 0, 5).iter().count(), 0);
+    }
+}
@@ -0,0 +1,514 @@
+use drawing::hsl::Hsl;
+use drawing::hsv::Hsv;
+use std::str::FromStr;
+use Error;
+
+#[derive(Hash, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Rgb {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+}
+
+#[allow(dead_code)]
+impl Rgb {
+    pub fn new(red: u8, green: u8, blue: u8) -> Rgb {
+        Rgb { red, green, blue }
+    }
+
+    /// Creates a color from a `0xRRGGBB` value.
+    pub fn from_u32(value: u32) -> Rgb {
+        Rgb {
+            red: (value >> 16) as u8,
+            green: (value >> 8) as u8,
+            blue: value as u8,
+        }
+    }
+
+    /// Returns the color as a `0xRRGGBB` value.
+    pub fn to_u32(&self) -> u32 {
+        (self.red as u32) << 16 | (self.green as u32) << 8 | self.blue as u32
+    }
+
+    /// Parses a `#rgb` or `#rrggbb` hex string. The `#` is optional.
+    pub fn from_hex(text: &str) -> Option<Rgb> {
+        let digits = text.trim_start_matches('#');
+
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        match digits.len() {
+            3 => {
+                let value = u32::from_str_radix(digits, 16).ok()?;
+                let expand = |nibble: u32| (nibble * 17) as u8;
+
+                Some(Rgb::new(
+                    expand(value >> 8 & 0xF),
+                    expand(value >> 4 & 0xF),
+                    expand(value & 0xF),
+                ))
+            }
+            6 => Some(Rgb::from_u32(u32::from_str_radix(digits, 16).ok()?)),
+            _ => None,
+        }
+    }
+
+    /// Formats the color as a `#rrggbb` hex string.
+    pub fn to_hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.red, self.green, self.blue)
+    }
+
+    /// Looks up a CSS color name, ignoring the case.
+    pub fn from_name(name: &str) -> Option<Rgb> {
+        let name = name.to_ascii_lowercase();
+
+        CSS_COLORS
+            .iter()
+            .find(|(css_name, _)| *css_name == name)
+            .map(|(_, value)| Rgb::from_u32(*value))
+    }
+
+    /// Parses either a hex string, with or without its `#`, or a CSS color
+    /// name.
+    pub fn parse(text: &str) -> Option<Rgb> {
+        let text = text.trim();
+
+        Rgb::from_hex(text).or_else(|| Rgb::from_name(text))
+    }
+
+    pub fn to_hsl(&self) -> Hsl {
+        let (r, g, b) = self.unit_channels();
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let delta = max - min;
+        let lightness = (max + min) / 2.0;
+
+        if delta == 0.0 {
+            return Hsl::new(0.0, 0.0, lightness);
+        }
+
+        let saturation = if lightness > 0.5 {
+            delta / (2.0 - max - min)
+        } else {
+            delta / (max + min)
+        };
+
+        Hsl::new(hue_of(r, g, b, max, delta), saturation, lightness)
+    }
+
+    pub fn from_hsl(hsl: Hsl) -> Rgb {
+        let saturation = clamp_unit(hsl.saturation);
+        let lightness = clamp_unit(hsl.lightness);
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+
+        from_chroma(hsl.hue, chroma, lightness - chroma / 2.0)
+    }
+
+    pub fn to_hsv(&self) -> Hsv {
+        let (r, g, b) = self.unit_channels();
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let delta = max - min;
+
+        if delta == 0.0 {
+            return Hsv::new(0.0, 0.0, max);
+        }
+
+        Hsv::new(hue_of(r, g, b, max, delta), delta / max, max)
+    }
+
+    pub fn from_hsv(hsv: Hsv) -> Rgb {
+        let saturation = clamp_unit(hsv.saturation);
+        let value = clamp_unit(hsv.value);
+        let chroma = value * saturation;
+
+        from_chroma(hsv.hue, chroma, value - chroma)
+    }
+
+    /// Interpolates linearly between two colors, with `t` between 0 and 1.
+    pub fn lerp(&self, other: Rgb, t: f64) -> Rgb {
+        let t = clamp_unit(t);
+        let channel = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+
+        Rgb::new(
+            channel(self.red, other.red),
+            channel(self.green, other.green),
+            channel(self.blue, other.blue),
+        )
+    }
+
+    /// Mixes two colors in linear light, which unlike `lerp` keeps the
+    /// perceived brightness of the blend. A `weight` of 1 gives `other`.
+    pub fn mix(&self, other: Rgb, weight: f64) -> Rgb {
+        let weight = clamp_unit(weight);
+        let channel = |a: u8, b: u8| {
+            let a = to_linear(a as f64 / 255.0);
+            let b = to_linear(b as f64 / 255.0);
+            (from_linear(a + (b - a) * weight) * 255.0).round() as u8
+        };
+
+        Rgb::new(
+            channel(self.red, other.red),
+            channel(self.green, other.green),
+            channel(self.blue, other.blue),
+        )
+    }
+
+    /// Converts the color to the CIE L*a*b* space.
+    pub fn to_lab(&self) -> (f64, f64, f64) {
+        let (r, g, b) = self.unit_channels();
+        let (r, g, b) = (to_linear(r), to_linear(g), to_linear(b));
+
+        let x = (0.4124 * r + 0.3576 * g + 0.1805 * b) / 0.95047;
+        let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+        let z = (0.0193 * r + 0.1192 * g + 0.9505 * b) / 1.08883;
+
+        let f = |t: f64| {
+            if t > 0.008856 {
+                t.cbrt()
+            } else {
+                7.787 * t + 16.0 / 116.0
+            }
+        };
+
+        let (fx, fy, fz) = (f(x), f(y), f(z));
+
+        (116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz))
+    }
+
+    /// Gets the perceptual distance between two colors, as the euclidean
+    /// distance in the L*a*b* space (CIE76).
+    pub fn distance(&self, other: Rgb) -> f64 {
+        let (l1, a1, b1) = self.to_lab();
+        let (l2, a2, b2) = other.to_lab();
+
+        ((l1 - l2).powi(2) + (a1 - a2).powi(2) + (b1 - b2).powi(2)).sqrt()
+    }
+
+    #[inline]
+    fn unit_channels(&self) -> (f64, f64, f64) {
+        (
+            self.red as f64 / 255.0,
+            self.green as f64 / 255.0,
+            self.blue as f64 / 255.0,
+        )
+    }
+}
+
+impl FromStr for Rgb {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Rgb, Error> {
+        Rgb::parse(text).ok_or(Error::InvalidArgument(
+            "The text is not a valid hex color or color name.",
+        ))
+    }
+}
+
+#[inline]
+fn clamp_unit(value: f64) -> f64 {
+    value.clamp(0.0, 1.0)
+}
+
+fn hue_of(r: f64, g: f64, b: f64, max: f64, delta: f64) -> f64 {
+    let hue = if max == r {
+        (g - b) / delta
+    } else if max == g {
+        (b - r) / delta + 2.0
+    } else {
+        (r - g) / delta + 4.0
+    };
+
+    (hue * 60.0 + 360.0) % 360.0
+}
+
+fn from_chroma(hue: f64, chroma: f64, m: f64) -> Rgb {
+    let sector = (hue % 360.0 + 360.0) % 360.0 / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+
+    let (r, g, b) = match sector as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+
+    let channel = |c: f64| (clamp_unit(c + m) * 255.0).round() as u8;
+
+    Rgb::new(channel(r), channel(g), channel(b))
+}
+
+#[inline]
+fn to_linear(c: f64) -> f64 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+#[inline]
+fn from_linear(c: f64) -> f64 {
+    if c <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+const CSS_COLORS: [(&str, u32); 148] = [
+    ("aliceblue", 0xF0F8FF),
+    ("antiquewhite", 0xFAEBD7),
+    ("aqua", 0x00FFFF),
+    ("aquamarine", 0x7FFFD4),
+    ("azure", 0xF0FFFF),
+    ("beige", 0xF5F5DC),
+    ("bisque", 0xFFE4C4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xFFEBCD),
+    ("blue", 0x0000FF),
+    ("blueviolet", 0x8A2BE2),
+    ("brown", 0xA52A2A),
+    ("burlywood", 0xDEB887),
+    ("cadetblue", 0x5F9EA0),
+    ("chartreuse", 0x7FFF00),
+    ("chocolate", 0xD2691E),
+    ("coral", 0xFF7F50),
+    ("cornflowerblue", 0x6495ED),
+    ("cornsilk", 0xFFF8DC),
+    ("crimson", 0xDC143C),
+    ("cyan", 0x00FFFF),
+    ("darkblue", 0x00008B),
+    ("darkcyan", 0x008B8B),
+    ("darkgoldenrod", 0xB8860B),
+    ("darkgray", 0xA9A9A9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xA9A9A9),
+    ("darkkhaki", 0xBDB76B),
+    ("darkmagenta", 0x8B008B),
+    ("darkolivegreen", 0x556B2F),
+    ("darkorange", 0xFF8C00),
+    ("darkorchid", 0x9932CC),
+    ("darkred", 0x8B0000),
+    ("darksalmon", 0xE9967A),
+    ("darkseagreen", 0x8FBC8F),
+    ("darkslateblue", 0x483D8B),
+    ("darkslategray", 0x2F4F4F),
+    ("darkslategrey", 0x2F4F4F),
+    ("darkturquoise", 0x00CED1),
+    ("darkviolet", 0x9400D3),
+    ("deeppink", 0xFF1493),
+    ("deepskyblue", 0x00BFFF),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1E90FF),
+    ("firebrick", 0xB22222),
+    ("floralwhite", 0xFFFAF0),
+    ("forestgreen", 0x228B22),
+    ("fuchsia", 0xFF00FF),
+    ("gainsboro", 0xDCDCDC),
+    ("ghostwhite", 0xF8F8FF),
+    ("gold", 0xFFD700),
+    ("goldenrod", 0xDAA520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xADFF2F),
+    ("grey", 0x808080),
+    ("honeydew", 0xF0FFF0),
+    ("hotpink", 0xFF69B4),
+    ("indianred", 0xCD5C5C),
+    ("indigo", 0x4B0082),
+    ("ivory", 0xFFFFF0),
+    ("khaki", 0xF0E68C),
+    ("lavender", 0xE6E6FA),
+    ("lavenderblush", 0xFFF0F5),
+    ("lawngreen", 0x7CFC00),
+    ("lemonchiffon", 0xFFFACD),
+    ("lightblue", 0xADD8E6),
+    ("lightcoral", 0xF08080),
+    ("lightcyan", 0xE0FFFF),
+    ("lightgoldenrodyellow", 0xFAFAD2),
+    ("lightgray", 0xD3D3D3),
+    ("lightgreen", 0x90EE90),
+    ("lightgrey", 0xD3D3D3),
+    ("lightpink", 0xFFB6C1),
+    ("lightsalmon", 0xFFA07A),
+    ("lightseagreen", 0x20B2AA),
+    ("lightskyblue", 0x87CEFA),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xB0C4DE),
+    ("lightyellow", 0xFFFFE0),
+    ("lime", 0x00FF00),
+    ("limegreen", 0x32CD32),
+    ("linen", 0xFAF0E6),
+    ("magenta", 0xFF00FF),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66CDAA),
+    ("mediumblue", 0x0000CD),
+    ("mediumorchid", 0xBA55D3),
+    ("mediumpurple", 0x9370DB),
+    ("mediumseagreen", 0x3CB371),
+    ("mediumslateblue", 0x7B68EE),
+    ("mediumspringgreen", 0x00FA9A),
+    ("mediumturquoise", 0x48D1CC),
+    ("mediumvioletred", 0xC71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xF5FFFA),
+    ("mistyrose", 0xFFE4E1),
+    ("moccasin", 0xFFE4B5),
+    ("navajowhite", 0xFFDEAD),
+    ("navy", 0x000080),
+    ("oldlace", 0xFDF5E6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6B8E23),
+    ("orange", 0xFFA500),
+    ("orangered", 0xFF4500),
+    ("orchid", 0xDA70D6),
+    ("palegoldenrod", 0xEEE8AA),
+    ("palegreen", 0x98FB98),
+    ("paleturquoise", 0xAFEEEE),
+    ("palevioletred", 0xDB7093),
+    ("papayawhip", 0xFFEFD5),
+    ("peachpuff", 0xFFDAB9),
+    ("peru", 0xCD853F),
+    ("pink", 0xFFC0CB),
+    ("plum", 0xDDA0DD),
+    ("powderblue", 0xB0E0E6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xFF0000),
+    ("rosybrown", 0xBC8F8F),
+    ("royalblue", 0x4169E1),
+    ("saddlebrown", 0x8B4513),
+    ("salmon", 0xFA8072),
+    ("sandybrown", 0xF4A460),
+    ("seagreen", 0x2E8B57),
+    ("seashell", 0xFFF5EE),
+    ("sienna", 0xA0522D),
+    ("silver", 0xC0C0C0),
+    ("skyblue", 0x87CEEB),
+    ("slateblue", 0x6A5ACD),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xFFFAFA),
+    ("springgreen", 0x00FF7F),
+    ("steelblue", 0x4682B4),
+    ("tan", 0xD2B48C),
+    ("teal", 0x008080),
+    ("thistle", 0xD8BFD8),
+    ("tomato", 0xFF6347),
+    ("turquoise", 0x40E0D0),
+    ("violet", 0xEE82EE),
+    ("wheat", 0xF5DEB3),
+    ("white", 0xFFFFFF),
+    ("whitesmoke", 0xF5F5F5),
+    ("yellow", 0xFFFF00),
+    ("yellowgreen", 0x9ACD32),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLES: [u32; 8] = [
+        0x000000, 0xFFFFFF, 0xFF0000, 0x00FF00, 0x0000FF, 0xFF8800, 0x336699, 0x808080,
+    ];
+
+    #[test]
+    fn hex_strings() {
+        assert_eq!(Rgb::from_hex("#ff8800"), Some(Rgb::new(255, 136, 0)));
+        assert_eq!(Rgb::from_hex("FF8800"), Some(Rgb::new(255, 136, 0)));
+        assert_eq!(Rgb::from_hex("#f80"), Some(Rgb::new(255, 136, 0)));
+        assert_eq!(Rgb::from_hex("#ff880"), None);
+        assert_eq!(Rgb::from_hex("#gg8800"), None);
+        assert_eq!(Rgb::from_hex("+f8800"), None);
+        assert_eq!(Rgb::new(255, 136, 0).to_hex(), "#ff8800");
+        assert_eq!(Rgb::from_u32(0x123456).to_u32(), 0x123456);
+    }
+
+    #[test]
+    fn parse_takes_hex_and_names() {
+        assert_eq!(Rgb::parse(" #ff8800 "), Some(Rgb::new(255, 136, 0)));
+        assert_eq!(Rgb::parse("ff8800"), Some(Rgb::new(255, 136, 0)));
+        assert_eq!(Rgb::parse("Orange"), Some(Rgb::new(255, 165, 0)));
+        assert_eq!(Rgb::parse("not a color"), None);
+        assert!("navy".parse::<Rgb>().is_ok());
+        assert!("#navy".parse::<Rgb>().is_err());
+    }
+
+    #[test]
+    fn hsl_conversions() {
+        let hsl = Rgb::new(255, 0, 0).to_hsl();
+        assert_eq!((hsl.hue, hsl.saturation, hsl.lightness), (0.0, 1.0, 0.5));
+
+        let hsl = Rgb::new(0, 0, 255).to_hsl();
+        assert_eq!(hsl.hue, 240.0);
+
+        assert_eq!(
+            Rgb::from_hsl(Hsl::new(120.0, 1.0, 0.25)),
+            Rgb::new(0, 128, 0)
+        );
+        assert_eq!(
+            Rgb::from_hsl(Hsl::new(-240.0, 2.0, 0.5)),
+            Rgb::new(0, 255, 0)
+        );
+
+        for value in &SAMPLES {
+            let rgb = Rgb::from_u32(*value);
+            assert_eq!(Rgb::from_hsl(rgb.to_hsl()), rgb);
+        }
+    }
+
+    #[test]
+    fn hsv_conversions() {
+        let hsv = Rgb::new(255, 136, 0).to_hsv();
+        assert_eq!((hsv.saturation, hsv.value), (1.0, 1.0));
+        assert!((hsv.hue - 32.0).abs() < 0.01);
+
+        assert_eq!(
+            Rgb::from_hsv(Hsv::new(300.0, 1.0, 1.0)),
+            Rgb::new(255, 0, 255)
+        );
+
+        for value in &SAMPLES {
+            let rgb = Rgb::from_u32(*value);
+            assert_eq!(Rgb::from_hsv(rgb.to_hsv()), rgb);
+        }
+    }
+
+    #[test]
+    fn lerp_and_mix() {
+        let black = Rgb::new(0, 0, 0);
+        let white = Rgb::new(255, 255, 255);
+
+        assert_eq!(black.lerp(white, 0.0), black);
+        assert_eq!(black.lerp(white, 0.5), Rgb::new(128, 128, 128));
+        assert_eq!(black.lerp(white, 2.0), white);
+
+        assert_eq!(black.mix(white, 0.0), black);
+        assert_eq!(black.mix(white, 1.0), white);
+        // half the light is brighter than half the value in sRGB.
+        assert_eq!(black.mix(white, 0.5), Rgb::new(188, 188, 188));
+    }
+
+    #[test]
+    fn lab_distance() {
+        let (l, a, b) = Rgb::new(255, 255, 255).to_lab();
+        assert!((l - 100.0).abs() < 0.1 && a.abs() < 0.1 && b.abs() < 0.1);
+
+        let (l, _, _) = Rgb::new(0, 0, 0).to_lab();
+        assert!(l.abs() < 0.01);
+
+        let red = Rgb::new(255, 0, 0);
+        let orange = Rgb::new(255, 136, 0);
+        let blue = Rgb::new(0, 0, 255);
+
+        assert_eq!(red.distance(red), 0.0);
+        assert_eq!(red.distance(blue), blue.distance(red));
+        assert!(red.distance(orange) < red.distance(blue));
+    }
+}
@@ -0,0 +1,112 @@
+use drawing::color::Color;
+use drawing::palette::Palette;
+
+/// Gets the index of a color in the ANSI 16 color table.
+pub fn color_to_u8(color: Color) -> u8 {
+    match color {
+        Color::Black => 0,
+        Color::DarkRed => 1,
+        Color::DarkGreen => 2,
+        Color::DarkYellow => 3,
+        Color::DarkBlue => 4,
+        Color::DarkMagenta => 5,
+        Color::DarkCyan => 6,
+        Color::Grey => 7,
+        Color::DarkGrey => 8,
+        Color::Red => 9,
+        Color::Green => 10,
+        Color::Yellow => 11,
+        Color::Blue => 12,
+        Color::Magenta => 13,
+        Color::Cyan => 14,
+        Color::White => 15,
+    }
+}
+
+/// Gets the color at an index of the ANSI 16 color table.
+pub fn color_from_u8(index: u8) -> Color {
+    match index & 0x0f {
+        0 => Color::Black,
+        1 => Color::DarkRed,
+        2 => Color::DarkGreen,
+        3 => Color::DarkYellow,
+        4 => Color::DarkBlue,
+        5 => Color::DarkMagenta,
+        6 => Color::DarkCyan,
+        7 => Color::Grey,
+        8 => Color::DarkGrey,
+        9 => Color::Red,
+        10 => Color::Green,
+        11 => Color::Yellow,
+        12 => Color::Blue,
+        13 => Color::Magenta,
+        14 => Color::Cyan,
+        _ => Color::White,
+    }
+}
+
+/// Builds the SGR sequence selecting a foreground and a background color,
+/// using true color when a palette is set.
+pub fn get_sgr(foreground: Color, background: Color, palette: Option<&Palette>) -> String {
+    match palette {
+        Some(palette) => {
+            let foreground = palette.get(foreground);
+            let background = palette.get(background);
+
+            format!(
+                "\x1b[38;2;{};{};{};48;2;{};{};{}m",
+                foreground.red,
+                foreground.green,
+                foreground.blue,
+                background.red,
+                background.green,
+                background.blue
+            )
+        }
+        None => {
+            let foreground = color_to_u8(foreground);
+            let background = color_to_u8(background);
+
+            format!(
+                "\x1b[{};{}m",
+                if foreground < 8 {
+                    30 + foreground
+                } else {
+                    82 + foreground
+                },
+                if background < 8 {
+                    40 + background
+                } else {
+                    92 + background
+                }
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use drawing::rgb::Rgb;
+
+    #[test]
+    fn indexes_round_trip() {
+        for color in Color::to_vec() {
+            assert_eq!(color_from_u8(color_to_u8(color)), color);
+        }
+    }
+
+    #[test]
+    fn sgr_sequences() {
+        assert_eq!(get_sgr(Color::DarkRed, Color::Black, None), "\x1b[31;40m");
+        assert_eq!(get_sgr(Color::White, Color::Blue, None), "\x1b[97;104m");
+
+        let mut palette = Palette::default();
+        palette.set(Color::Red, Rgb::new(1, 2, 3));
+
+        assert_eq!(
+            get_sgr(Color::Red, Color::Black, Some(&palette)),
+            "\x1b[38;2;1;2;3;48;2;0;0;0m"
+        );
+    }
+}
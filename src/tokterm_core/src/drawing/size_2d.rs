@@ -1,3 +1,5 @@
+use std::ops::{Add, Mul, Sub};
+
 #[derive(Hash, Debug, Copy, Clone, PartialEq, Eq)]
 pub struct Size2d {
     pub width: usize,
@@ -37,3 +39,43 @@ impl Size2d {
         self.width == 0 && self.height == 0
     }
 }
+
+impl Add for Size2d {
+    type Output = Size2d;
+
+    fn add(self, size: Size2d) -> Size2d {
+        Size2d::new(self.width + size.width, self.height + size.height)
+    }
+}
+
+/// Subtracts each dimension, saturating at zero.
+impl Sub for Size2d {
+    type Output = Size2d;
+
+    fn sub(self, size: Size2d) -> Size2d {
+        Size2d::new(
+            self.width.saturating_sub(size.width),
+            self.height.saturating_sub(size.height),
+        )
+    }
+}
+
+impl Mul<usize> for Size2d {
+    type Output = Size2d;
+
+    fn mul(self, factor: usize) -> Size2d {
+        Size2d::new(self.width * factor, self.height * factor)
+    }
+}
+
+impl From<(usize, usize)> for Size2d {
+    fn from((width, height): (usize, usize)) -> Size2d {
+        Size2d::new(width, height)
+    }
+}
+
+impl From<Size2d> for (usize, usize) {
+    fn from(size: Size2d) -> (usize, usize) {
+        (size.width, size.height)
+    }
+}
@@ -0,0 +1,57 @@
+use std::error;
+use std::fmt;
+use std::io;
+
+/// Enumerates the errors reported by the toolkit and its backends.
+#[derive(Debug)]
+pub enum Error {
+    /// The terminal was closed, or its input or output reached the end.
+    TerminalClosed,
+    /// The terminal or the backend does not support the operation.
+    NotSupported(&'static str),
+    /// An input or output operation failed.
+    Io(&'static str, io::Error),
+    /// A native library call failed with the given return code.
+    Native(&'static str, i32),
+    /// The operation can not be done in the current state, like drawing a
+    /// line without a stroke.
+    InvalidOperation(&'static str),
+    /// An argument was not valid, like a malformed color string.
+    InvalidArgument(&'static str),
+}
+
+impl Error {
+    /// Wraps an I/O error, reporting a closed pipe or an unexpected end of
+    /// file as `Error::TerminalClosed`.
+    pub fn io(context: &'static str, error: io::Error) -> Error {
+        match error.kind() {
+            io::ErrorKind::BrokenPipe
+            | io::ErrorKind::UnexpectedEof
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::ConnectionAborted => Error::TerminalClosed,
+            _ => Error::Io(context, error),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::TerminalClosed => write!(f, "The terminal was closed."),
+            Error::NotSupported(message) => write!(f, "{}", message),
+            Error::Io(message, _) => write!(f, "{}", message),
+            Error::Native(message, code) => write!(f, "{} (return code {})", message, code),
+            Error::InvalidOperation(message) => write!(f, "{}", message),
+            Error::InvalidArgument(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Io(_, error) => Some(error),
+            _ => None,
+        }
+    }
+}
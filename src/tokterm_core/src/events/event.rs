@@ -7,9 +7,30 @@ use input::key::Key;
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum MouseEventType {
     MouseMove,
-    Click,
+    /// A button was pressed, the one told by `MouseEvent::button`.
+    MouseDown,
+    /// A button was released, the one told by `MouseEvent::button`.
+    MouseUp,
     DoubleClick,
+    /// The wheel was scrolled, `wheel_delta` being positive upwards.
     Wheel,
+    /// The wheel was scrolled sideways, `wheel_delta` being positive to the
+    /// right.
+    HorizontalWheel,
+}
+
+/// Enumerates the mouse buttons.
+#[allow(dead_code)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum MouseButton {
+    None,
+    Left,
+    Middle,
+    Right,
+    Extra1,
+    Extra2,
+    Extra3,
+    Extra4,
 }
 
 /// Enumerates all the possible keyboard event types.
@@ -29,13 +50,20 @@ pub enum WindowEventType {
     WindowFocus,
     WindowLostFocus,
     WindowClose,
+    /// The application was continued after being suspended, and has to draw
+    /// the whole screen again.
+    WindowResume,
 }
 
 /// Represents a mouse event like mouse move or mouse down.
 #[allow(dead_code)]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct MouseEvent {
     pub event_type: MouseEventType,
+    /// The button pressed or released, `MouseButton::None` for the other
+    /// events.
+    pub button: MouseButton,
+    /// Whether each button is held after the event.
     pub left_button: bool,
     pub middle_button: bool,
     pub right_button: bool,
@@ -45,11 +73,15 @@ pub struct MouseEvent {
     pub extra_button_4: bool,
     pub position: Point2d,
     pub wheel_delta: i16,
+    /// The modifier keys held during the event.
+    pub control: bool,
+    pub shift: bool,
+    pub menu: bool,
 }
 
 /// Represents a keyboard event like key down or key up.
 #[allow(dead_code)]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct KeyboardEvent {
     pub event_type: KeyboardEventType,
     pub key: Key,
@@ -65,7 +97,7 @@ pub struct KeyboardEvent {
 
 /// Represents a window event like window moved or window resized.
 #[allow(dead_code)]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct WindowEvent {
     pub event_type: WindowEventType,
     pub position: Point2d,
@@ -74,9 +106,13 @@ pub struct WindowEvent {
 
 /// Event object enumeration can be one of the valid event types.
 #[allow(dead_code)]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Event {
     Mouse(MouseEvent),
     Keyboard(KeyboardEvent),
     Window(WindowEvent),
+    /// The text in the clipboard, replying to `Terminal::request_clipboard`.
+    Clipboard(String),
+    /// Text pasted by the user, delivered at once instead of as keys.
+    Paste(String),
 }
@@ -348,4 +348,205 @@ impl Key {
             Key::OemClear => 170,
         }
     }
+
+    /// Gets the key matching a value returned by `to_u32`.
+    pub fn from_u32(value: u32) -> Option<Key> {
+        match value {
+            0 => Some(Key::None),
+            1 => Some(Key::LeftButton),
+            2 => Some(Key::RightButton),
+            3 => Some(Key::Cancel),
+            4 => Some(Key::MiddleButton),
+            5 => Some(Key::XButton1),
+            6 => Some(Key::XButton2),
+            8 => Some(Key::Back),
+            9 => Some(Key::Tab),
+            10 => Some(Key::Clear),
+            11 => Some(Key::Return),
+            12 => Some(Key::Shift),
+            13 => Some(Key::Control),
+            14 => Some(Key::Menu),
+            15 => Some(Key::Pause),
+            16 => Some(Key::Capital),
+            17 => Some(Key::KanaHangelHangul),
+            18 => Some(Key::Junja),
+            19 => Some(Key::Final),
+            20 => Some(Key::HanjaKanji),
+            21 => Some(Key::Escape),
+            22 => Some(Key::Convert),
+            23 => Some(Key::NonConvert),
+            24 => Some(Key::Accept),
+            25 => Some(Key::ModeChange),
+            26 => Some(Key::Space),
+            27 => Some(Key::Prior),
+            28 => Some(Key::Next),
+            29 => Some(Key::End),
+            30 => Some(Key::Home),
+            31 => Some(Key::Left),
+            32 => Some(Key::Up),
+            33 => Some(Key::Right),
+            34 => Some(Key::Down),
+            35 => Some(Key::Select),
+            36 => Some(Key::Print),
+            37 => Some(Key::Execute),
+            38 => Some(Key::Snapshot),
+            39 => Some(Key::Insert),
+            40 => Some(Key::Delete),
+            41 => Some(Key::Help),
+            42 => Some(Key::Key0),
+            43 => Some(Key::Key1),
+            44 => Some(Key::Key2),
+            45 => Some(Key::Key3),
+            46 => Some(Key::Key4),
+            47 => Some(Key::Key5),
+            48 => Some(Key::Key6),
+            49 => Some(Key::Key7),
+            50 => Some(Key::Key8),
+            51 => Some(Key::Key9),
+            52 => Some(Key::A),
+            53 => Some(Key::B),
+            54 => Some(Key::C),
+            55 => Some(Key::D),
+            56 => Some(Key::E),
+            57 => Some(Key::F),
+            58 => Some(Key::G),
+            59 => Some(Key::H),
+            60 => Some(Key::I),
+            61 => Some(Key::J),
+            62 => Some(Key::K),
+            63 => Some(Key::L),
+            64 => Some(Key::M),
+            65 => Some(Key::N),
+            66 => Some(Key::O),
+            67 => Some(Key::P),
+            68 => Some(Key::Q),
+            69 => Some(Key::R),
+            70 => Some(Key::S),
+            71 => Some(Key::T),
+            72 => Some(Key::U),
+            73 => Some(Key::V),
+            74 => Some(Key::W),
+            75 => Some(Key::X),
+            76 => Some(Key::Y),
+            77 => Some(Key::Z),
+            78 => Some(Key::LeftWin),
+            79 => Some(Key::RightWin),
+            80 => Some(Key::Apps),
+            81 => Some(Key::Sleep),
+            82 => Some(Key::NumPad0),
+            83 => Some(Key::NumPad1),
+            84 => Some(Key::NumPad2),
+            85 => Some(Key::NumPad3),
+            86 => Some(Key::NumPad4),
+            87 => Some(Key::NumPad5),
+            88 => Some(Key::NumPad6),
+            89 => Some(Key::NumPad7),
+            90 => Some(Key::NumPad8),
+            91 => Some(Key::NumPad9),
+            92 => Some(Key::Multiply),
+            93 => Some(Key::Add),
+            94 => Some(Key::Separator),
+            95 => Some(Key::Subtract),
+            96 => Some(Key::Decimal),
+            97 => Some(Key::Divide),
+            98 => Some(Key::F1),
+            99 => Some(Key::F2),
+            100 => Some(Key::F3),
+            101 => Some(Key::F4),
+            102 => Some(Key::F5),
+            103 => Some(Key::F6),
+            104 => Some(Key::F7),
+            105 => Some(Key::F8),
+            106 => Some(Key::F9),
+            107 => Some(Key::F10),
+            108 => Some(Key::F11),
+            109 => Some(Key::F12),
+            110 => Some(Key::F13),
+            111 => Some(Key::F14),
+            112 => Some(Key::F15),
+            113 => Some(Key::F16),
+            114 => Some(Key::F17),
+            115 => Some(Key::F18),
+            116 => Some(Key::F19),
+            117 => Some(Key::F20),
+            118 => Some(Key::F21),
+            119 => Some(Key::F22),
+            120 => Some(Key::F23),
+            121 => Some(Key::F24),
+            122 => Some(Key::NumLock),
+            123 => Some(Key::Scroll),
+            124 => Some(Key::LeftShift),
+            125 => Some(Key::RightShift),
+            126 => Some(Key::LeftControl),
+            127 => Some(Key::RightControl),
+            128 => Some(Key::LeftMenu),
+            129 => Some(Key::RightMenu),
+            130 => Some(Key::BrowserBack),
+            131 => Some(Key::BrowserForward),
+            132 => Some(Key::BrowserRefresh),
+            133 => Some(Key::BrowserStop),
+            134 => Some(Key::BrowserSearch),
+            135 => Some(Key::BrowserFavorites),
+            136 => Some(Key::BrowserHome),
+            137 => Some(Key::VolumeMute),
+            138 => Some(Key::VolumeDown),
+            139 => Some(Key::VolumeUp),
+            140 => Some(Key::MediaNextTrack),
+            141 => Some(Key::MediaPreviousTrack),
+            142 => Some(Key::MediaStop),
+            143 => Some(Key::MediaPlayPause),
+            144 => Some(Key::LaunchMail),
+            145 => Some(Key::LaunchMediaSelect),
+            146 => Some(Key::LaunchApp1),
+            147 => Some(Key::LaunchApp2),
+            148 => Some(Key::Oem1),
+            149 => Some(Key::Plus),
+            150 => Some(Key::Comma),
+            151 => Some(Key::Minus),
+            152 => Some(Key::Period),
+            153 => Some(Key::Oem2),
+            154 => Some(Key::Oem3),
+            155 => Some(Key::Oem4),
+            156 => Some(Key::Oem5),
+            157 => Some(Key::Oem6),
+            158 => Some(Key::Oem7),
+            159 => Some(Key::Oem8),
+            160 => Some(Key::ProcessKey),
+            161 => Some(Key::ImeProcess),
+            162 => Some(Key::Packet),
+            163 => Some(Key::Attn),
+            164 => Some(Key::CrSel),
+            165 => Some(Key::ExSel),
+            166 => Some(Key::EraseEof),
+            167 => Some(Key::Play),
+            168 => Some(Key::Zoom),
+            169 => Some(Key::PA1),
+            170 => Some(Key::OemClear),
+            _ => None,
+        }
+    }
+
+    /// Gets the key typing a character on a US layout, letters in either
+    /// case, or `Key::None` when the character needs shift or another layout.
+    pub fn from_char(character: char) -> Key {
+        match character {
+            'a'..='z' => Key::from_u32(Key::A.to_u32() + (character as u32 - 'a' as u32)),
+            'A'..='Z' => Key::from_u32(Key::A.to_u32() + (character as u32 - 'A' as u32)),
+            '0'..='9' => Key::from_u32(Key::Key0.to_u32() + (character as u32 - '0' as u32)),
+            ' ' => Some(Key::Space),
+            '+' | '=' => Some(Key::Plus),
+            ',' => Some(Key::Comma),
+            '-' => Some(Key::Minus),
+            '.' => Some(Key::Period),
+            ';' => Some(Key::Oem1),
+            '/' => Some(Key::Oem2),
+            '`' => Some(Key::Oem3),
+            '[' => Some(Key::Oem4),
+            '\\' => Some(Key::Oem5),
+            ']' => Some(Key::Oem6),
+            '\'' => Some(Key::Oem7),
+            _ => None,
+        }
+        .unwrap_or(Key::None)
+    }
 }
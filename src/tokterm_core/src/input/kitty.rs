@@ -0,0 +1,282 @@
+use events::event::{KeyboardEvent, KeyboardEventType};
+use input::key::Key;
+use std::char;
+use std::str;
+
+/// Pushes the enhancements of the kitty keyboard protocol: every key is
+/// reported as `CSI code ; modifiers : event ; text u`, on press, repeat and
+/// release, modifier keys included.
+pub const ENABLE_KITTY_KEYBOARD: &str = "\x1b[>31u";
+/// Pops the enhancements pushed by `ENABLE_KITTY_KEYBOARD`.
+pub const DISABLE_KITTY_KEYBOARD: &str = "\x1b[<u";
+/// Asks for the current enhancements. Only the terminals supporting the
+/// protocol reply, with `CSI ? flags u`.
+pub const KITTY_KEYBOARD_QUERY: &str = "\x1b[?u";
+
+const SHIFT: u32 = 1;
+const ALT: u32 = 2;
+const CONTROL: u32 = 4;
+const RELEASE: u32 = 3;
+
+/// Decodes the key reports of the kitty keyboard protocol. The held
+/// modifier keys are tracked to tell the left ones from the right ones,
+/// which the modifier bits don't.
+#[derive(Debug, Default)]
+pub struct KittyKeyboard {
+    enhanced: bool,
+    left_shift: bool,
+    right_shift: bool,
+    left_control: bool,
+    right_control: bool,
+    left_alt: bool,
+    right_alt: bool,
+}
+
+#[allow(dead_code)]
+impl KittyKeyboard {
+    pub fn new() -> KittyKeyboard {
+        KittyKeyboard::default()
+    }
+
+    /// Returns whether the terminal replied to `KITTY_KEYBOARD_QUERY`, so
+    /// keys are reported when released too.
+    #[inline]
+    pub fn is_enhanced(&self) -> bool {
+        self.enhanced
+    }
+
+    /// Returns whether a control sequence, given its parameters and final
+    /// byte, is a key report or the query reply to pass to `parse`. The
+    /// legacy forms are only taken once the protocol is enabled or when they
+    /// carry an event type, and the bracketed paste markers never are.
+    pub fn is_key_report(&self, parameters: &[u8], final_byte: u8) -> bool {
+        let enhanced = self.enhanced || parameters.contains(&b':');
+
+        match final_byte {
+            b'u' => true,
+            b'~' => enhanced && parameters != b"200" && parameters != b"201",
+            b'A'..=b'F' | b'H' | b'P' | b'Q' | b'S' => {
+                enhanced && parameters.first() != Some(&b'<')
+            }
+            _ => false,
+        }
+    }
+
+    /// Parses a key report. `None` is returned for the query reply, which
+    /// tells whether the protocol is enabled, and for the keys that have no
+    /// `Key`.
+    pub fn parse(&mut self, parameters: &[u8], final_byte: u8) -> Option<KeyboardEvent> {
+        if parameters.first() == Some(&b'?') {
+            let flags = parse_numbers(&parameters[1..]);
+            self.enhanced = flags.first().is_some_and(|flags| *flags != 0);
+            return None;
+        }
+
+        let mut fields = parameters.split(|b| *b == b';');
+        let codes = parse_numbers(fields.next().unwrap_or(b""));
+        let modifiers = parse_numbers(fields.next().unwrap_or(b""));
+        let text = parse_numbers(fields.next().unwrap_or(b""));
+
+        let code = codes.first().cloned().unwrap_or(1);
+        let shifted = codes.get(1).cloned().unwrap_or(0);
+        let bits = modifiers.first().cloned().unwrap_or(1).saturating_sub(1);
+        let pressed = modifiers.get(1).cloned().unwrap_or(1) != RELEASE;
+
+        let (key, character) = match final_byte {
+            b'u' => get_key(code)?,
+            b'~' => (get_tilde_key(code)?, '\0'),
+            b'A' => (Key::Up, '\0'),
+            b'B' => (Key::Down, '\0'),
+            b'C' => (Key::Right, '\0'),
+            b'D' => (Key::Left, '\0'),
+            b'E' => (Key::Clear, '\0'),
+            b'F' => (Key::End, '\0'),
+            b'H' => (Key::Home, '\0'),
+            b'P' => (Key::F1, '\0'),
+            b'Q' => (Key::F2, '\0'),
+            b'S' => (Key::F4, '\0'),
+            _ => return None,
+        };
+
+        let character = match text.first().and_then(|code| char::from_u32(*code)) {
+            Some(text) => text,
+            None if bits & SHIFT != 0 && shifted != 0 => {
+                char::from_u32(shifted).unwrap_or(character)
+            }
+            None => character,
+        };
+
+        let shift = self.update_modifier(
+            key,
+            (Key::LeftShift, Key::RightShift),
+            bits & SHIFT != 0,
+            pressed,
+        );
+        let control = self.update_modifier(
+            key,
+            (Key::LeftControl, Key::RightControl),
+            bits & CONTROL != 0,
+            pressed,
+        );
+        let alt = self.update_modifier(
+            key,
+            (Key::LeftMenu, Key::RightMenu),
+            bits & ALT != 0,
+            pressed,
+        );
+
+        Some(KeyboardEvent {
+            event_type: if pressed {
+                KeyboardEventType::KeyDown
+            } else {
+                KeyboardEventType::KeyUp
+            },
+            key,
+            key_code: character as u16,
+            character,
+            left_control: self.left_control || (control && !self.right_control),
+            left_shift: self.left_shift || (shift && !self.right_shift),
+            left_menu: self.left_alt || (alt && !self.right_alt),
+            right_control: self.right_control,
+            right_shift: self.right_shift,
+            right_menu: self.right_alt,
+        })
+    }
+
+    /// Updates the held state of a modifier, given its left and right keys,
+    /// returning whether it's active for the reported key. A report of the
+    /// modifier key itself doesn't count, since terminals disagree on whether
+    /// its bit is set, and a report without the bit releases both sides in
+    /// case their releases were missed.
+    fn update_modifier(&mut self, key: Key, sides: (Key, Key), bit: bool, pressed: bool) -> bool {
+        let (left, right) = match sides.0 {
+            Key::LeftShift => (&mut self.left_shift, &mut self.right_shift),
+            Key::LeftControl => (&mut self.left_control, &mut self.right_control),
+            _ => (&mut self.left_alt, &mut self.right_alt),
+        };
+
+        if key == sides.0 {
+            *left = pressed;
+        } else if key == sides.1 {
+            *right = pressed;
+        } else {
+            if !bit {
+                *left = false;
+                *right = false;
+            }
+
+            return bit;
+        }
+
+        false
+    }
+}
+
+/// Gets the key and character of a `CSI u` key code, which is either a
+/// unicode code point or one of the private use codes of the protocol.
+fn get_key(code: u32) -> Option<(Key, char)> {
+    let key = match code {
+        9 => return Some((Key::Tab, '\t')),
+        13 => return Some((Key::Return, '\r')),
+        27 => return Some((Key::Escape, '\x1b')),
+        127 => return Some((Key::Back, '\x08')),
+        57358 => Key::Capital,
+        57359 => Key::Scroll,
+        57360 => Key::NumLock,
+        57361 => Key::Snapshot,
+        57362 => Key::Pause,
+        57363 => Key::Apps,
+        57376..=57387 => Key::from_u32(Key::F13.to_u32() + (code - 57376))?,
+        57399..=57408 => {
+            let digit = code - 57399;
+            return Some((
+                Key::from_u32(Key::NumPad0.to_u32() + digit)?,
+                char::from_digit(digit, 10)?,
+            ));
+        }
+        57409 => return Some((Key::Decimal, '.')),
+        57410 => return Some((Key::Divide, '/')),
+        57411 => return Some((Key::Multiply, '*')),
+        57412 => return Some((Key::Subtract, '-')),
+        57413 => return Some((Key::Add, '+')),
+        57414 => return Some((Key::Return, '\r')),
+        57416 => return Some((Key::Separator, ',')),
+        57417 => Key::Left,
+        57418 => Key::Right,
+        57419 => Key::Up,
+        57420 => Key::Down,
+        57421 => Key::Prior,
+        57422 => Key::Next,
+        57423 => Key::Home,
+        57424 => Key::End,
+        57425 => Key::Insert,
+        57426 => Key::Delete,
+        57427 => Key::Clear,
+        57430 => Key::MediaPlayPause,
+        57432 => Key::MediaStop,
+        57435 => Key::MediaNextTrack,
+        57436 => Key::MediaPreviousTrack,
+        57438 => Key::VolumeDown,
+        57439 => Key::VolumeUp,
+        57440 => Key::VolumeMute,
+        57441 => Key::LeftShift,
+        57442 => Key::LeftControl,
+        57443 => Key::LeftMenu,
+        57444 => Key::LeftWin,
+        57447 => Key::RightShift,
+        57448 => Key::RightControl,
+        57449 => Key::RightMenu,
+        57450 => Key::RightWin,
+        _ => {
+            let character = char::from_u32(code).filter(|c| !c.is_control())?;
+            return Some((Key::from_char(character), character));
+        }
+    };
+
+    Some((key, '\0'))
+}
+
+/// Gets the key of a `CSI number ~` report.
+fn get_tilde_key(number: u32) -> Option<Key> {
+    let key = match number {
+        1 | 7 => Key::Home,
+        2 => Key::Insert,
+        3 => Key::Delete,
+        4 | 8 => Key::End,
+        5 => Key::Prior,
+        6 => Key::Next,
+        11 => Key::F1,
+        12 => Key::F2,
+        13 => Key::F3,
+        14 => Key::F4,
+        15 => Key::F5,
+        17 => Key::F6,
+        18 => Key::F7,
+        19 => Key::F8,
+        20 => Key::F9,
+        21 => Key::F10,
+        23 => Key::F11,
+        24 => Key::F12,
+        _ => return None,
+    };
+
+    Some(key)
+}
+
+/// Parses the sub-parameters of a field, separated by colons. The omitted
+/// ones are zero.
+fn parse_numbers(field: &[u8]) -> Vec<u32> {
+    if field.is_empty() {
+        return Vec::new();
+    }
+
+    field
+        .split(|b| *b == b':')
+        .map(|number| {
+            str::from_utf8(number)
+                .ok()
+                .and_then(|number| number.parse().ok())
+                .unwrap_or(0)
+        })
+        .collect()
+}
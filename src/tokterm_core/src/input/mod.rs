@@ -1,3 +1,6 @@
 pub mod key;
 pub mod keyboard_state;
+pub mod kitty;
 pub mod mouse_state;
+pub mod parser;
+pub mod sgr_mouse;
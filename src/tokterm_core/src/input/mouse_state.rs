@@ -1,5 +1,5 @@
 use drawing::point_2d::Point2d;
-use events::event::MouseEvent;
+use events::event::{MouseButton, MouseEvent};
 
 pub struct MouseState {
     pub left_button: bool,
@@ -36,4 +36,18 @@ impl MouseState {
         self.extra_button_4 = mouse.extra_button_4;
         self.position = mouse.position;
     }
+
+    /// Returns whether a button is held.
+    pub fn is_held(&self, button: MouseButton) -> bool {
+        match button {
+            MouseButton::None => false,
+            MouseButton::Left => self.left_button,
+            MouseButton::Middle => self.middle_button,
+            MouseButton::Right => self.right_button,
+            MouseButton::Extra1 => self.extra_button_1,
+            MouseButton::Extra2 => self.extra_button_2,
+            MouseButton::Extra3 => self.extra_button_3,
+            MouseButton::Extra4 => self.extra_button_4,
+        }
+    }
 }
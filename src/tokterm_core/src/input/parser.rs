@@ -0,0 +1,831 @@
+use drawing::point_2d::Point2d;
+use drawing::size_2d::Size2d;
+use events::event::{Event, KeyboardEvent, KeyboardEventType, WindowEvent, WindowEventType};
+use input::key::Key;
+use input::kitty::KittyKeyboard;
+use input::sgr_mouse::SgrMouse;
+use std::char;
+use std::str;
+use std::time::{Duration, Instant};
+use system::clipboard;
+use system::paste::{find_paste_end, get_paste_text, PASTE_END, PASTE_START};
+
+/// How long an escape waits for the rest of a sequence before it's taken as
+/// the escape key. Terminals send a sequence at once, nobody types that fast.
+pub const ESCAPE_TIMEOUT: Duration = Duration::from_millis(50);
+
+/// How long a paste or an OSC reply waits for its end before its bytes are
+/// taken as typed keys, since `ESC ]` is also Alt+] followed by more keys.
+pub const REPLY_TIMEOUT: Duration = Duration::from_secs(2);
+
+const SHIFT: u32 = 1;
+const ALT: u32 = 2;
+const CONTROL: u32 = 4;
+const META: u32 = 8;
+
+/// Turns the raw bytes read from a terminal into events, keeping the
+/// incomplete sequences until the rest of their bytes arrive. Since an
+/// escape can't be told from the start of a sequence, it waits too, until
+/// `check_timeout` or `flush` takes it as the escape key.
+#[derive(Debug, Default)]
+pub struct InputParser {
+    pending: Vec<u8>,
+    received: Option<Instant>,
+    limit: Option<usize>,
+    keyboard: KittyKeyboard,
+    mouse: SgrMouse,
+}
+
+#[allow(dead_code)]
+impl InputParser {
+    pub fn new() -> InputParser {
+        InputParser::default()
+    }
+
+    /// Creates a parser holding at most `limit` bytes of incomplete
+    /// sequences, for input coming from untrusted clients. Past it, the held
+    /// bytes are taken as typed keys, even a paste or a reply.
+    pub fn with_limit(limit: usize) -> InputParser {
+        InputParser {
+            limit: Some(limit),
+            ..InputParser::default()
+        }
+    }
+
+    /// Returns whether an incomplete sequence waits for more bytes.
+    #[inline]
+    pub fn is_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Parses the bytes read, following the ones kept from previous reads.
+    pub fn parse(&mut self, bytes: &[u8]) -> Vec<Event> {
+        if !bytes.is_empty() {
+            self.received = Some(Instant::now());
+        }
+
+        self.pending.extend_from_slice(bytes);
+
+        let mut events = Vec::new();
+        self.parse_pending(&mut events);
+
+        if self.limit.is_some_and(|limit| self.pending.len() > limit) {
+            self.flush_keys(true, &mut events);
+        }
+
+        events
+    }
+
+    /// Flushes the incomplete sequence once no bytes arrived for
+    /// `ESCAPE_TIMEOUT`, or `REPLY_TIMEOUT` for a paste or a reply, to be
+    /// called whenever there's nothing to read.
+    pub fn check_timeout(&mut self) -> Vec<Event> {
+        let mut events = Vec::new();
+
+        match self.received.map(|received| received.elapsed()) {
+            Some(elapsed) if elapsed >= REPLY_TIMEOUT => self.flush_keys(true, &mut events),
+            Some(elapsed) if elapsed >= ESCAPE_TIMEOUT => self.flush_keys(false, &mut events),
+            _ => (),
+        }
+
+        events
+    }
+
+    /// Takes the incomplete sequence as typed keys: its escape is the escape
+    /// key, or alt along with the only character following it. Pastes and
+    /// OSC replies are kept, since they're never typed and may be long, until
+    /// `check_timeout` gives up on them after `REPLY_TIMEOUT`.
+    pub fn flush(&mut self) -> Vec<Event> {
+        let mut events = Vec::new();
+        self.flush_keys(false, &mut events);
+        events
+    }
+
+    /// Takes the held bytes as typed keys, the pastes and replies too when
+    /// `replies` is set.
+    fn flush_keys(&mut self, replies: bool, events: &mut Vec<Event>) {
+        while self.is_pending() && (replies || !self.is_reply_pending()) {
+            let (character, alt, length) = match self.pending[..] {
+                [0x1b, next] if next.is_ascii() => (next as char, true, 2),
+                // a reply that never ended started with Alt+] or Alt+[.
+                [0x1b, next, ..] if replies && next.is_ascii() => (next as char, true, 2),
+                [0x1b, ..] => ('\x1b', false, 1),
+                // the rest of a UTF-8 character never arrived.
+                _ => (char::REPLACEMENT_CHARACTER, false, 1),
+            };
+
+            push_key_press(events, get_char_event(character, alt));
+            self.pending.drain(..length);
+            self.parse_pending(events);
+        }
+    }
+
+    fn is_reply_pending(&self) -> bool {
+        self.pending.starts_with(PASTE_START)
+            || (self.pending.starts_with(b"\x1b]") && self.pending.len() > 2)
+    }
+
+    fn parse_pending(&mut self, events: &mut Vec<Event>) {
+        let mut index = 0;
+
+        while index < self.pending.len() {
+            let input = &self.pending[index..];
+
+            match parse_sequence(input, &mut self.keyboard, &mut self.mouse, events) {
+                Some(consumed) => index += consumed,
+                None => break,
+            }
+        }
+
+        self.pending.drain(..index);
+    }
+}
+
+/// Parses the sequence at the start of the input, returning the amount of
+/// bytes consumed or `None` when the sequence is incomplete.
+fn parse_sequence(
+    input: &[u8],
+    keyboard: &mut KittyKeyboard,
+    mouse: &mut SgrMouse,
+    events: &mut Vec<Event>,
+) -> Option<usize> {
+    if input[0] != 0x1b {
+        let (character, length) = decode_char(input)?;
+        push_key_press(events, get_char_event(character, false));
+        return Some(length);
+    }
+
+    match *input.get(1)? {
+        b'[' => parse_csi_sequence(input, keyboard, mouse, events),
+        b']' => {
+            let (payload, length) = split_osc(&input[2..])?;
+            events.extend(clipboard::parse_reply(payload).map(Event::Clipboard));
+            Some(length + 2)
+        }
+        b'O' => {
+            // some terminals put the modifiers between SS3 and the final byte.
+            let end = input[2..]
+                .iter()
+                .position(|b| !b.is_ascii_digit() && *b != b';')?
+                + 2;
+
+            match parse_ss3(&input[2..end], input[end]) {
+                Some(event) => {
+                    push_key_press(events, Some(event));
+                    Some(end + 1)
+                }
+                None => {
+                    push_key_press(events, get_char_event('O', true));
+                    Some(2)
+                }
+            }
+        }
+        0x1b => {
+            let next = *input.get(2)?;
+
+            if next != b'[' && next != b'O' {
+                push_key_press(events, get_char_event('\x1b', true));
+                return Some(2);
+            }
+
+            // alt puts an escape before the sequences of the keys too.
+            let mut prefixed = Vec::new();
+            let length = parse_sequence(&input[1..], keyboard, mouse, &mut prefixed)?;
+
+            events.extend(prefixed.into_iter().map(|event| match event {
+                Event::Keyboard(event) => Event::Keyboard(KeyboardEvent {
+                    left_menu: true,
+                    ..event
+                }),
+                event => event,
+            }));
+
+            Some(length + 1)
+        }
+        _ => {
+            let (character, length) = decode_char(&input[1..])?;
+            push_key_press(events, get_char_event(character, true));
+            Some(length + 1)
+        }
+    }
+}
+
+/// Parses a control sequence, `CSI parameters final`, which may be a paste,
+/// a mouse or kitty key report, or a key of the legacy encodings.
+fn parse_csi_sequence(
+    input: &[u8],
+    keyboard: &mut KittyKeyboard,
+    mouse: &mut SgrMouse,
+    events: &mut Vec<Event>,
+) -> Option<usize> {
+    let end = (2..input.len()).find(|end| is_final_byte(&input[2..*end], input[*end]))?;
+    let parameters = &input[2..end];
+
+    if &input[..=end] == PASTE_START {
+        // the whole paste is kept until its end arrives.
+        let text = &input[end + 1..];
+        let length = find_paste_end(text)?;
+        events.push(Event::Paste(get_paste_text(&text[..length])));
+        return Some(end + 1 + length + PASTE_END.len());
+    }
+
+    // the linux console sends F1 to F5 as `CSI [ A` to `CSI [ E`.
+    if parameters.is_empty() && input[end] == b'[' {
+        let byte = *input.get(end + 1)?;
+
+        if (b'A'..=b'E').contains(&byte) {
+            let key = Key::from_u32(Key::F1.to_u32() + (byte - b'A') as u32);
+            push_key_press(
+                events,
+                key.map(|key| get_key_event(key, '\0', false, false, false)),
+            );
+        }
+
+        return Some(end + 2);
+    }
+
+    // the legacy mouse reports, `CSI M` and three bytes, are skipped since
+    // the SGR ones are asked for along with them.
+    if parameters.is_empty() && input[end] == b'M' {
+        return if input.len() < end + 4 {
+            None
+        } else {
+            Some(end + 4)
+        };
+    }
+
+    if SgrMouse::is_mouse_report(parameters, input[end]) {
+        events.extend(mouse.parse(parameters, input[end]).map(Event::Mouse));
+    } else if keyboard.is_key_report(parameters, input[end]) {
+        let event = keyboard.parse(parameters, input[end]).map(Event::Keyboard);
+
+        if keyboard.is_enhanced() {
+            events.extend(event);
+        } else {
+            push_key_press(events, event);
+        }
+    } else {
+        push_key_press(events, parse_csi(parameters, input[end]));
+    }
+
+    Some(end + 1)
+}
+
+/// Checks whether a byte ends a control sequence. rxvt ends the shifted keys,
+/// `CSI number $`, with an intermediate byte, which is followed by the final
+/// one in replies like `CSI ? 1 ; 2 $ y`.
+fn is_final_byte(parameters: &[u8], byte: u8) -> bool {
+    (0x40..=0x7e).contains(&byte) || (byte == b'$' && parameters.iter().all(u8::is_ascii_digit))
+}
+
+/// Splits the payload of an OSC reply from its terminator, either `BEL` or
+/// `ESC \`, returning it with the amount of bytes consumed or `None` when
+/// the terminator hasn't arrived yet.
+fn split_osc(input: &[u8]) -> Option<(&[u8], usize)> {
+    let end = input.iter().position(|b| *b == 0x07 || *b == 0x1b)?;
+
+    if input[end] == 0x07 {
+        return Some((&input[..end], end + 1));
+    }
+
+    if input.len() < end + 2 {
+        return None;
+    }
+
+    Some((&input[..end], end + 2))
+}
+
+fn decode_char(input: &[u8]) -> Option<(char, usize)> {
+    let length = match input[0] {
+        0x00..=0x7f => 1,
+        0xc0..=0xdf => 2,
+        0xe0..=0xef => 3,
+        0xf0..=0xf7 => 4,
+        _ => return Some((char::REPLACEMENT_CHARACTER, 1)),
+    };
+
+    if input.len() < length {
+        return None;
+    }
+
+    match str::from_utf8(&input[..length]) {
+        Ok(text) => text.chars().next().map(|character| (character, length)),
+        Err(_) => Some((char::REPLACEMENT_CHARACTER, 1)),
+    }
+}
+
+/// Parses a key of the legacy encodings. xterm adds the modifiers as a
+/// second parameter, one plus their bits, while rxvt tells them by the final
+/// byte.
+fn parse_csi(parameters: &[u8], final_byte: u8) -> Option<Event> {
+    let numbers = parse_numbers(parameters);
+    let modifiers = match final_byte {
+        b'$' | b'a'..=b'd' => SHIFT,
+        b'^' => CONTROL,
+        b'@' => CONTROL | SHIFT,
+        _ => numbers.get(1).cloned().unwrap_or(1).saturating_sub(1),
+    };
+    let (control, alt, shift) = get_modifiers(modifiers);
+
+    let key = match final_byte {
+        b'A' | b'a' => Key::Up,
+        b'B' | b'b' => Key::Down,
+        b'C' | b'c' => Key::Right,
+        b'D' | b'd' => Key::Left,
+        b'E' | b'G' => Key::Clear,
+        b'H' => Key::Home,
+        b'F' => Key::End,
+        b'P' => Key::F1,
+        b'Q' => Key::F2,
+        b'R' => Key::F3,
+        b'S' => Key::F4,
+        b'Z' => return Some(get_key_event(Key::Tab, '\t', control, alt, true)),
+        b'I' if parameters.is_empty() => return Some(get_focus_event(true)),
+        b'O' if parameters.is_empty() => return Some(get_focus_event(false)),
+        // xterm's modifyOtherKeys reports the keys that have no sequence of
+        // their own as `CSI 27 ; modifiers ; code ~`.
+        b'~' if numbers.first() == Some(&27) => {
+            let character = char::from_u32(*numbers.get(2)?)?;
+            let (key, character, control_character) = get_char_key(character)?;
+            return Some(get_key_event(
+                key,
+                character,
+                control || control_character,
+                alt,
+                shift,
+            ));
+        }
+        b'~' | b'$' | b'^' | b'@' => get_tilde_key(numbers.first().cloned().unwrap_or(0))?,
+        _ => return None,
+    };
+
+    Some(get_key_event(key, '\0', control, alt, shift))
+}
+
+/// Parses the final byte of an SS3 sequence, sent for the cursor keys in
+/// application mode, the first function keys and the keypad in application
+/// mode.
+fn parse_ss3(parameters: &[u8], final_byte: u8) -> Option<Event> {
+    let modifiers = match final_byte {
+        // rxvt reports the arrows with control held with lowercase letters.
+        b'a'..=b'd' => CONTROL,
+        _ => parse_numbers(parameters)
+            .last()
+            .cloned()
+            .unwrap_or(1)
+            .saturating_sub(1),
+    };
+    let (control, alt, shift) = get_modifiers(modifiers);
+
+    let (key, character) = match final_byte {
+        b'A' | b'a' => (Key::Up, '\0'),
+        b'B' | b'b' => (Key::Down, '\0'),
+        b'C' | b'c' => (Key::Right, '\0'),
+        b'D' | b'd' => (Key::Left, '\0'),
+        b'E' => (Key::Clear, '\0'),
+        b'H' => (Key::Home, '\0'),
+        b'F' => (Key::End, '\0'),
+        b'P' => (Key::F1, '\0'),
+        b'Q' => (Key::F2, '\0'),
+        b'R' => (Key::F3, '\0'),
+        b'S' => (Key::F4, '\0'),
+        b'M' => (Key::Return, '\r'),
+        b'X' => (Key::Plus, '='),
+        b'j' => (Key::Multiply, '*'),
+        b'k' => (Key::Add, '+'),
+        b'l' => (Key::Separator, ','),
+        b'm' => (Key::Subtract, '-'),
+        b'n' => (Key::Decimal, '.'),
+        b'o' => (Key::Divide, '/'),
+        b'p'..=b'y' => {
+            let digit = (final_byte - b'p') as u32;
+            (
+                Key::from_u32(Key::NumPad0.to_u32() + digit)?,
+                char::from_digit(digit, 10)?,
+            )
+        }
+        _ => return None,
+    };
+
+    Some(get_key_event(key, character, control, alt, shift))
+}
+
+/// Gets the key of a `CSI number ~` sequence, rxvt's shifted function keys
+/// included.
+fn get_tilde_key(number: u32) -> Option<Key> {
+    let key = match number {
+        1 | 7 => Key::Home,
+        2 => Key::Insert,
+        3 => Key::Delete,
+        4 | 8 => Key::End,
+        5 => Key::Prior,
+        6 => Key::Next,
+        11 => Key::F1,
+        12 => Key::F2,
+        13 => Key::F3,
+        14 => Key::F4,
+        15 => Key::F5,
+        17 => Key::F6,
+        18 => Key::F7,
+        19 => Key::F8,
+        20 => Key::F9,
+        21 => Key::F10,
+        23 => Key::F11,
+        24 => Key::F12,
+        25 => Key::F13,
+        26 => Key::F14,
+        28 => Key::F15,
+        29 => Key::F16,
+        31 => Key::F17,
+        32 => Key::F18,
+        33 => Key::F19,
+        34 => Key::F20,
+        _ => return None,
+    };
+
+    Some(key)
+}
+
+fn parse_numbers(parameters: &[u8]) -> Vec<u32> {
+    parameters
+        .split(|b| *b == b';')
+        .map(|number| {
+            str::from_utf8(number)
+                .ok()
+                .and_then(|number| number.parse().ok())
+                .unwrap_or(0)
+        })
+        .collect()
+}
+
+/// Gets whether control, alt and shift are held from the modifier bits,
+/// meta counting as alt.
+fn get_modifiers(bits: u32) -> (bool, bool, bool) {
+    (
+        bits & CONTROL != 0,
+        bits & (ALT | META) != 0,
+        bits & SHIFT != 0,
+    )
+}
+
+fn get_char_event(character: char, alt: bool) -> Option<Event> {
+    let (key, character, control) = get_char_key(character)?;
+
+    Some(get_key_event(
+        key,
+        character,
+        control,
+        alt,
+        character.is_ascii_uppercase(),
+    ))
+}
+
+/// Gets the key of a character, along with whether control is held, since
+/// it turns the keys it's typed with into control characters.
+fn get_char_key(character: char) -> Option<(Key, char, bool)> {
+    let key = match character {
+        '\r' | '\n' => (Key::Return, '\r', false),
+        '\t' => (Key::Tab, '\t', false),
+        '\x7f' | '\x08' => (Key::Back, '\x08', false),
+        '\x1b' => (Key::Escape, '\x1b', false),
+        '\0' => (Key::Space, ' ', true),
+        '\x01'..='\x1a' => {
+            let letter = (b'a' + character as u8 - 1) as char;
+            (Key::from_char(letter), letter, true)
+        }
+        // Ctrl+\ is the usual detach key of terminal multiplexers.
+        '\x1c' => (Key::Oem5, '\\', true),
+        '\x1d' => (Key::Oem6, ']', true),
+        '\x1e' => (Key::Key6, '6', true),
+        '\x1f' => (Key::Minus, '-', true),
+        _ if character.is_control() => return None,
+        _ => (Key::from_char(character), character, false),
+    };
+
+    Some(key)
+}
+
+/// Gets the event of a focus report, which doesn't tell the window size.
+fn get_focus_event(focused: bool) -> Event {
+    Event::Window(WindowEvent {
+        event_type: if focused {
+            WindowEventType::WindowFocus
+        } else {
+            WindowEventType::WindowLostFocus
+        },
+        position: Point2d::empty(),
+        size: Size2d::empty(),
+    })
+}
+
+/// Pushes an event of the legacy encodings, where keys are only reported
+/// when pressed: their release follows right away, so they don't look held.
+fn push_key_press(events: &mut Vec<Event>, event: Option<Event>) {
+    match event {
+        Some(Event::Keyboard(event)) if event.event_type == KeyboardEventType::KeyDown => {
+            events.push(Event::Keyboard(event));
+            events.push(Event::Keyboard(KeyboardEvent {
+                event_type: KeyboardEventType::KeyUp,
+                ..event
+            }));
+        }
+        event => events.extend(event),
+    }
+}
+
+fn get_key_event(key: Key, character: char, control: bool, alt: bool, shift: bool) -> Event {
+    Event::Keyboard(KeyboardEvent {
+        event_type: KeyboardEventType::KeyDown,
+        key,
+        key_code: character as u16,
+        character,
+        left_control: control,
+        left_shift: shift,
+        left_menu: alt,
+        right_control: false,
+        right_shift: false,
+        right_menu: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use events::event::{MouseButton, MouseEvent, MouseEventType};
+
+    /// Gets the events of a key press, its release following right away.
+    fn press(event: Option<Event>) -> Vec<Event> {
+        let mut events = Vec::new();
+        push_key_press(&mut events, event);
+        events
+    }
+
+    fn press_key(key: Key, character: char, control: bool, alt: bool, shift: bool) -> Vec<Event> {
+        press(Some(get_key_event(key, character, control, alt, shift)))
+    }
+
+    fn press_char(character: char, alt: bool) -> Vec<Event> {
+        press(get_char_event(character, alt))
+    }
+
+    fn parse(bytes: &[u8]) -> Vec<Event> {
+        InputParser::new().parse(bytes)
+    }
+
+    /// Makes the parser believe its last bytes arrived `elapsed` ago.
+    fn wait(parser: &mut InputParser, elapsed: Duration) {
+        parser.received = Some(Instant::now() - elapsed);
+    }
+
+    fn get_mouse_event(event_type: MouseEventType, left_button: bool) -> Event {
+        Event::Mouse(MouseEvent {
+            event_type,
+            button: MouseButton::Left,
+            left_button,
+            middle_button: false,
+            right_button: false,
+            extra_button_1: false,
+            extra_button_2: false,
+            extra_button_3: false,
+            extra_button_4: false,
+            position: Point2d::new(4, 2),
+            wheel_delta: 0,
+            control: false,
+            shift: false,
+            menu: false,
+        })
+    }
+
+    #[test]
+    fn csi_keys_with_modifiers() {
+        assert_eq!(
+            parse(b"\x1b[A"),
+            press_key(Key::Up, '\0', false, false, false)
+        );
+        assert_eq!(
+            parse(b"\x1b[1;5A"),
+            press_key(Key::Up, '\0', true, false, false)
+        );
+        assert_eq!(
+            parse(b"\x1b[3;4~"),
+            press_key(Key::Delete, '\0', false, true, true)
+        );
+        assert_eq!(
+            parse(b"\x1b[15;2~"),
+            press_key(Key::F5, '\0', false, false, true)
+        );
+        assert_eq!(
+            parse(b"\x1b[Z"),
+            press_key(Key::Tab, '\t', false, false, true)
+        );
+        assert_eq!(
+            parse(b"\x1b[2$"),
+            press_key(Key::Insert, '\0', false, false, true)
+        );
+    }
+
+    #[test]
+    fn ss3_keys_with_modifiers() {
+        assert_eq!(
+            parse(b"\x1bOP"),
+            press_key(Key::F1, '\0', false, false, false)
+        );
+        assert_eq!(
+            parse(b"\x1bO5P"),
+            press_key(Key::F1, '\0', true, false, false)
+        );
+        assert_eq!(
+            parse(b"\x1bO1;3D"),
+            press_key(Key::Left, '\0', false, true, false)
+        );
+        assert_eq!(
+            parse(b"\x1bOa"),
+            press_key(Key::Up, '\0', true, false, false)
+        );
+    }
+
+    #[test]
+    fn keypad_keys() {
+        assert_eq!(
+            parse(b"\x1bOp"),
+            press_key(Key::NumPad0, '0', false, false, false)
+        );
+        assert_eq!(
+            parse(b"\x1bOy"),
+            press_key(Key::NumPad9, '9', false, false, false)
+        );
+        assert_eq!(
+            parse(b"\x1bOk"),
+            press_key(Key::Add, '+', false, false, false)
+        );
+        assert_eq!(
+            parse(b"\x1bOM"),
+            press_key(Key::Return, '\r', false, false, false)
+        );
+    }
+
+    #[test]
+    fn alt_is_an_escape_prefix() {
+        assert_eq!(parse(b"\x1ba"), press_char('a', true));
+        assert_eq!(
+            parse(b"\x1b\x1b[A"),
+            press_key(Key::Up, '\0', false, true, false)
+        );
+        assert_eq!(
+            parse(b"\x1b\x1bOP"),
+            press_key(Key::F1, '\0', false, true, false)
+        );
+    }
+
+    #[test]
+    fn utf8_split_between_reads() {
+        let mut parser = InputParser::new();
+
+        assert_eq!(parser.parse(&[0xc3]), []);
+        assert!(parser.is_pending());
+        assert_eq!(parser.parse(&[0xa9]), press_char('é', false));
+        assert!(!parser.is_pending());
+    }
+
+    #[test]
+    fn sgr_mouse_press_and_release() {
+        let mut parser = InputParser::new();
+
+        assert_eq!(
+            parser.parse(b"\x1b[<0;5;3M"),
+            [get_mouse_event(MouseEventType::MouseDown, true)]
+        );
+        assert_eq!(
+            parser.parse(b"\x1b[<0;5;3m"),
+            [get_mouse_event(MouseEventType::MouseUp, false)]
+        );
+    }
+
+    #[test]
+    fn bracketed_paste_split_between_reads() {
+        let mut parser = InputParser::new();
+
+        assert_eq!(parser.parse(b"\x1b[200~hel"), []);
+        assert_eq!(parser.parse(b"lo\x1b[2"), []);
+        assert_eq!(
+            parser.parse(b"01~a"),
+            [
+                &[Event::Paste(String::from("hello"))][..],
+                &press_char('a', false)
+            ]
+            .concat()
+        );
+    }
+
+    #[test]
+    fn focus_in_and_out() {
+        assert_eq!(parse(b"\x1b[I"), [get_focus_event(true)]);
+        assert_eq!(parse(b"\x1b[O"), [get_focus_event(false)]);
+    }
+
+    #[test]
+    fn kitty_keys() {
+        let mut parser = InputParser::new();
+
+        // until the terminal confirms the protocol, reports are presses.
+        let events = parser.parse(b"\x1b[97;5u");
+        assert_eq!(events.len(), 2);
+
+        match events[0] {
+            Event::Keyboard(event) => {
+                assert_eq!(event.event_type, KeyboardEventType::KeyDown);
+                assert_eq!(event.key, Key::A);
+                assert!(event.left_control);
+            }
+            ref event => panic!("unexpected event {:?}", event),
+        }
+
+        assert_eq!(parser.parse(b"\x1b[?1u"), []);
+
+        let events = parser.parse(b"\x1b[97;5:1u\x1b[97;5:3u");
+        let event_types: Vec<_> = events
+            .iter()
+            .map(|event| match *event {
+                Event::Keyboard(event) => (event.event_type, event.key),
+                ref event => panic!("unexpected event {:?}", event),
+            })
+            .collect();
+
+        assert_eq!(
+            event_types,
+            [
+                (KeyboardEventType::KeyDown, Key::A),
+                (KeyboardEventType::KeyUp, Key::A)
+            ]
+        );
+    }
+
+    #[test]
+    fn lone_escape_waits_for_the_timeout() {
+        let mut parser = InputParser::new();
+
+        assert_eq!(parser.parse(b"\x1b"), []);
+        assert_eq!(parser.check_timeout(), []);
+        assert!(parser.is_pending());
+
+        wait(&mut parser, ESCAPE_TIMEOUT);
+        assert_eq!(parser.check_timeout(), press_char('\x1b', false));
+        assert!(!parser.is_pending());
+    }
+
+    #[test]
+    fn escape_followed_by_a_sequence() {
+        let mut parser = InputParser::new();
+
+        assert_eq!(parser.parse(b"\x1b"), []);
+        assert_eq!(
+            parser.parse(b"[A"),
+            press_key(Key::Up, '\0', false, false, false)
+        );
+    }
+
+    #[test]
+    fn unfinished_replies_wait_for_their_own_timeout() {
+        let mut parser = InputParser::new();
+
+        assert_eq!(parser.parse(b"\x1b]a"), []);
+
+        wait(&mut parser, ESCAPE_TIMEOUT);
+        assert_eq!(parser.check_timeout(), []);
+
+        wait(&mut parser, REPLY_TIMEOUT);
+        assert_eq!(
+            parser.check_timeout(),
+            [press_char(']', true), press_char('a', false)].concat()
+        );
+        assert!(!parser.is_pending());
+    }
+
+    #[test]
+    fn unfinished_pastes_are_limited() {
+        let mut parser = InputParser::with_limit(8);
+
+        assert_eq!(parser.parse(b"\x1b[200~ab"), []);
+        assert_eq!(
+            parser.parse(b"c"),
+            [
+                press_char('[', true),
+                press_char('2', false),
+                press_char('0', false),
+                press_char('0', false),
+                press_char('~', false),
+                press_char('a', false),
+                press_char('b', false),
+                press_char('c', false),
+            ]
+            .concat()
+        );
+        assert!(!parser.is_pending());
+    }
+
+    #[test]
+    fn mode_reports_are_consumed() {
+        assert_eq!(parse(b"\x1b[?1;2$yq"), press_char('q', false));
+        assert_eq!(parse(b"\x1b[4;1$yq"), press_char('q', false));
+    }
+}
@@ -0,0 +1,132 @@
+use drawing::point_2d::Point2d;
+use events::event::{MouseButton, MouseEvent, MouseEventType};
+use std::str;
+
+/// Makes the terminal report the mouse presses, releases and motion as SGR
+/// (1006) sequences, `CSI < code ; x ; y M` or `m` on release, which have
+/// no coordinate limit.
+pub const ENABLE_SGR_MOUSE: &str = "\x1b[?1003h\x1b[?1006h";
+pub const DISABLE_SGR_MOUSE: &str = "\x1b[?1006l\x1b[?1003l";
+
+const SHIFT: u32 = 4;
+const META: u32 = 8;
+const CONTROL: u32 = 16;
+const MOTION: u32 = 32;
+const WHEEL: u32 = 64;
+const EXTRA: u32 = 128;
+
+/// Decodes the SGR mouse reports, keeping which buttons are held since the
+/// reports only tell the button that changed.
+#[derive(Debug, Default)]
+pub struct SgrMouse {
+    left_button: bool,
+    middle_button: bool,
+    right_button: bool,
+    extra_button_1: bool,
+    extra_button_2: bool,
+    extra_button_3: bool,
+    extra_button_4: bool,
+}
+
+#[allow(dead_code)]
+impl SgrMouse {
+    pub fn new() -> SgrMouse {
+        SgrMouse::default()
+    }
+
+    /// Returns whether a control sequence, given its parameters and final
+    /// byte, is an SGR mouse report.
+    pub fn is_mouse_report(parameters: &[u8], final_byte: u8) -> bool {
+        parameters.first() == Some(&b'<') && (final_byte == b'M' || final_byte == b'm')
+    }
+
+    /// Parses an SGR mouse report, given its parameters and final byte.
+    /// `None` is returned when the report is malformed.
+    pub fn parse(&mut self, parameters: &[u8], final_byte: u8) -> Option<MouseEvent> {
+        let numbers = parse_numbers(parameters.strip_prefix(b"<")?)?;
+
+        if numbers.len() != 3 || (final_byte != b'M' && final_byte != b'm') {
+            return None;
+        }
+
+        let code = numbers[0];
+        let button = match code & (EXTRA | WHEEL | 0b11) {
+            0 => MouseButton::Left,
+            1 => MouseButton::Middle,
+            2 => MouseButton::Right,
+            128 => MouseButton::Extra1,
+            129 => MouseButton::Extra2,
+            130 => MouseButton::Extra3,
+            131 => MouseButton::Extra4,
+            _ => MouseButton::None,
+        };
+
+        let (event_type, wheel_delta) = if code & (EXTRA | WHEEL) == WHEEL {
+            match code & 0b11 {
+                0 => (MouseEventType::Wheel, 1),
+                1 => (MouseEventType::Wheel, -1),
+                2 => (MouseEventType::HorizontalWheel, -1),
+                _ => (MouseEventType::HorizontalWheel, 1),
+            }
+        } else if code & MOTION != 0 {
+            (MouseEventType::MouseMove, 0)
+        } else if final_byte == b'M' {
+            (MouseEventType::MouseDown, 0)
+        } else {
+            (MouseEventType::MouseUp, 0)
+        };
+
+        match event_type {
+            MouseEventType::MouseDown => self.set_button(button, true),
+            MouseEventType::MouseUp => self.set_button(button, false),
+            // the motion reports tell one of the held buttons, or none when
+            // all were released, mending any press or release missed.
+            MouseEventType::MouseMove if button == MouseButton::None => *self = SgrMouse::new(),
+            MouseEventType::MouseMove => self.set_button(button, true),
+            _ => (),
+        }
+
+        Some(MouseEvent {
+            event_type,
+            button: match event_type {
+                MouseEventType::MouseDown | MouseEventType::MouseUp => button,
+                _ => MouseButton::None,
+            },
+            left_button: self.left_button,
+            middle_button: self.middle_button,
+            right_button: self.right_button,
+            extra_button_1: self.extra_button_1,
+            extra_button_2: self.extra_button_2,
+            extra_button_3: self.extra_button_3,
+            extra_button_4: self.extra_button_4,
+            position: Point2d::new(
+                numbers[1].saturating_sub(1) as i32,
+                numbers[2].saturating_sub(1) as i32,
+            ),
+            wheel_delta,
+            control: code & CONTROL != 0,
+            shift: code & SHIFT != 0,
+            menu: code & META != 0,
+        })
+    }
+
+    fn set_button(&mut self, button: MouseButton, held: bool) {
+        match button {
+            MouseButton::Left => self.left_button = held,
+            MouseButton::Middle => self.middle_button = held,
+            MouseButton::Right => self.right_button = held,
+            MouseButton::Extra1 => self.extra_button_1 = held,
+            MouseButton::Extra2 => self.extra_button_2 = held,
+            MouseButton::Extra3 => self.extra_button_3 = held,
+            MouseButton::Extra4 => self.extra_button_4 = held,
+            MouseButton::None => (),
+        }
+    }
+}
+
+fn parse_numbers(parameters: &[u8]) -> Option<Vec<u32>> {
+    parameters
+        .split(|b| *b == b';')
+        .map(|number| str::from_utf8(number).ok()?.parse().ok())
+        .collect()
+}
@@ -1,8 +1,14 @@
+#[cfg(unix)]
+extern crate libc;
+
 use std::result;
 
-pub type Result<T> = result::Result<T, &'static str>;
+pub type Result<T> = result::Result<T, Error>;
 
 pub mod drawing;
+pub mod error;
 pub mod events;
 pub mod input;
 pub mod system;
+
+pub use error::Error;
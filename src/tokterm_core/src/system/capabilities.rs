@@ -0,0 +1,296 @@
+use std::env;
+use system::terminfo::{self, Terminfo};
+
+/// Enumerates the amount of colors a terminal can present.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ColorDepth {
+    NoColor,
+    Ansi8,
+    Ansi16,
+    Indexed256,
+    TrueColor,
+}
+
+impl ColorDepth {
+    /// Gets the depth able to present a given amount of colors.
+    pub fn from_colors(colors: u32) -> ColorDepth {
+        match colors {
+            0..=7 => ColorDepth::NoColor,
+            8..=15 => ColorDepth::Ansi8,
+            16..=255 => ColorDepth::Ansi16,
+            256..=16_777_215 => ColorDepth::Indexed256,
+            _ => ColorDepth::TrueColor,
+        }
+    }
+
+    /// Gets the amount of colors of the depth.
+    pub fn colors(&self) -> u32 {
+        match self {
+            ColorDepth::NoColor => 2,
+            ColorDepth::Ansi8 => 8,
+            ColorDepth::Ansi16 => 16,
+            ColorDepth::Indexed256 => 256,
+            ColorDepth::TrueColor => 16_777_216,
+        }
+    }
+}
+
+/// Enumerates the mouse reporting modes a terminal supports.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MouseSupport {
+    None,
+    /// The X10/normal tracking modes, with coordinates limited to 223.
+    Normal,
+    /// The SGR 1006 extended mode, with explicit releases.
+    Sgr,
+}
+
+/// Describes what a terminal can do, so applications can degrade gracefully
+/// instead of assuming colors and mouse support everywhere.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Capabilities {
+    pub color_depth: ColorDepth,
+    pub bold: bool,
+    pub dim: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub reverse: bool,
+    pub mouse: MouseSupport,
+    pub alternate_screen: bool,
+    pub unicode: bool,
+    pub title: bool,
+    /// Whether the base colors can be redefined, like with `set_palette`.
+    pub change_palette: bool,
+    /// Whether the system clipboard can be set and queried with OSC 52.
+    pub clipboard: bool,
+    /// Whether pasted text can be told apart from typed keys.
+    pub bracketed_paste: bool,
+    /// Whether the terminal reports when its window gains or loses focus.
+    pub focus_events: bool,
+    /// Whether the kitty keyboard protocol may be requested. Terminals
+    /// without it ignore the request, so only their reply to the query turns
+    /// it on.
+    pub kitty_keyboard: bool,
+}
+
+/// Terminal name prefixes of the xterm compatible emulators, which support
+/// SGR mouse, the alternate screen, titles, the clipboard, bracketed paste and
+/// focus events even when their terminfo entries don't say so. The kitty
+/// keyboard protocol is requested from them too, since it's only used once
+/// the terminal confirms it.
+const XTERM_LIKE: &[&str] = &[
+    "xterm",
+    "rxvt",
+    "screen",
+    "tmux",
+    "alacritty",
+    "kitty",
+    "foot",
+    "wezterm",
+    "contour",
+    "vte",
+    "gnome",
+    "konsole",
+    "iterm",
+];
+
+#[allow(dead_code)]
+impl Capabilities {
+    /// Capabilities of a terminal that can only print text.
+    pub fn none() -> Capabilities {
+        Capabilities {
+            color_depth: ColorDepth::NoColor,
+            bold: false,
+            dim: false,
+            italic: false,
+            underline: false,
+            reverse: false,
+            mouse: MouseSupport::None,
+            alternate_screen: false,
+            unicode: false,
+            title: false,
+            change_palette: false,
+            clipboard: false,
+            bracketed_paste: false,
+            focus_events: false,
+            kitty_keyboard: false,
+        }
+    }
+
+    /// Capabilities of a basic ANSI terminal: 16 colors, the common
+    /// attributes and unicode.
+    pub fn basic() -> Capabilities {
+        Capabilities {
+            color_depth: ColorDepth::Ansi16,
+            bold: true,
+            underline: true,
+            reverse: true,
+            unicode: true,
+            ..Capabilities::none()
+        }
+    }
+
+    /// Detects the capabilities of the current terminal from `TERM`,
+    /// `COLORTERM`, `NO_COLOR`, the locale and the terminfo entry.
+    pub fn from_env() -> Capabilities {
+        let term = env::var("TERM").ok();
+        let terminfo = term.as_ref().and_then(|term| Terminfo::load(term).ok());
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty());
+        let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+
+        Capabilities::detect(
+            term.as_deref(),
+            env::var("COLORTERM").ok().as_deref(),
+            no_color,
+            locale.as_deref(),
+            terminfo.as_ref(),
+        )
+    }
+
+    /// Computes the capabilities from the given environment values and
+    /// terminfo entry, falling back to the terminal name when there is no
+    /// entry.
+    pub fn detect(
+        term: Option<&str>,
+        colorterm: Option<&str>,
+        no_color: bool,
+        locale: Option<&str>,
+        terminfo: Option<&Terminfo>,
+    ) -> Capabilities {
+        let term = term.unwrap_or("").to_lowercase();
+        let xterm_like = XTERM_LIKE.iter().any(|prefix| term.starts_with(prefix));
+
+        let mut capabilities = match terminfo {
+            Some(terminfo) => Capabilities::from_terminfo(terminfo),
+            None if term.is_empty() || term == "dumb" => Capabilities::none(),
+            None => Capabilities::from_name(&term),
+        };
+
+        if xterm_like {
+            // xterm compatible emulators present the bright colors even when
+            // their entries only declare 8.
+            if capabilities.color_depth == ColorDepth::Ansi8 {
+                capabilities.color_depth = ColorDepth::Ansi16;
+            }
+
+            capabilities.mouse = MouseSupport::Sgr;
+            capabilities.alternate_screen = true;
+            capabilities.title = true;
+            capabilities.clipboard = true;
+            capabilities.bracketed_paste = true;
+            capabilities.focus_events = true;
+            capabilities.kitty_keyboard = true;
+        }
+
+        // a dumb terminal stays dumb, whatever `COLORTERM` is inherited.
+        if let Some(colorterm) = colorterm.filter(|_| term != "dumb") {
+            if colorterm == "truecolor" || colorterm == "24bit" {
+                capabilities.color_depth = ColorDepth::TrueColor;
+            }
+        }
+
+        if no_color {
+            capabilities.color_depth = ColorDepth::NoColor;
+        }
+
+        if let Some(locale) = locale {
+            let locale = locale.to_lowercase();
+            capabilities.unicode = locale.contains("utf-8") || locale.contains("utf8");
+        }
+
+        capabilities
+    }
+
+    /// Reads the capabilities of a terminfo entry.
+    pub fn from_terminfo(terminfo: &Terminfo) -> Capabilities {
+        let colors = terminfo
+            .get_number(terminfo::MAX_COLORS)
+            .or_else(|| terminfo.get_extended_number("colors"))
+            .unwrap_or(0);
+        let true_color = terminfo.get_extended_flag("Tc") || terminfo.get_extended_flag("RGB");
+
+        Capabilities {
+            color_depth: if true_color {
+                ColorDepth::TrueColor
+            } else {
+                ColorDepth::from_colors(colors as u32)
+            },
+            bold: terminfo.get_string(terminfo::ENTER_BOLD_MODE).is_some(),
+            dim: terminfo.get_string(terminfo::ENTER_DIM_MODE).is_some(),
+            italic: terminfo.get_string(terminfo::ENTER_ITALICS_MODE).is_some(),
+            underline: terminfo
+                .get_string(terminfo::ENTER_UNDERLINE_MODE)
+                .is_some(),
+            reverse: terminfo.get_string(terminfo::ENTER_REVERSE_MODE).is_some(),
+            mouse: match terminfo.get_string(terminfo::KEY_MOUSE) {
+                Some(b"\x1b[<") => MouseSupport::Sgr,
+                Some(_) => MouseSupport::Normal,
+                None => MouseSupport::None,
+            },
+            alternate_screen: terminfo.get_string(terminfo::ENTER_CA_MODE).is_some(),
+            unicode: true,
+            title: (terminfo.get_flag(terminfo::HAS_STATUS_LINE)
+                && terminfo.get_string(terminfo::TO_STATUS_LINE).is_some())
+                || terminfo.get_extended_flag("XT"),
+            change_palette: terminfo.get_flag(terminfo::CAN_CHANGE),
+            clipboard: terminfo.get_extended_string("Ms").is_some(),
+            bracketed_paste: terminfo.get_extended_string("BE").is_some(),
+            focus_events: terminfo.get_extended_string("fe").is_some(),
+            kitty_keyboard: false,
+        }
+    }
+
+    /// Guesses the capabilities from the terminal name alone.
+    fn from_name(term: &str) -> Capabilities {
+        let mut capabilities = Capabilities::basic();
+
+        if term.contains("256color") {
+            capabilities.color_depth = ColorDepth::Indexed256;
+        } else if term.contains("mono") || term.starts_with("vt") {
+            capabilities.color_depth = ColorDepth::NoColor;
+        } else if term == "linux" || term == "ansi" {
+            capabilities.color_depth = ColorDepth::Ansi8;
+        }
+
+        capabilities
+    }
+
+    #[inline]
+    pub fn has_color(&self) -> bool {
+        self.color_depth != ColorDepth::NoColor
+    }
+
+    #[inline]
+    pub fn has_mouse(&self) -> bool {
+        self.mouse != MouseSupport::None
+    }
+}
+
+impl Default for Capabilities {
+    fn default() -> Capabilities {
+        Capabilities::basic()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colorterm_raises_the_color_depth() {
+        let capabilities =
+            Capabilities::detect(Some("vt100"), Some("truecolor"), false, None, None);
+
+        assert_eq!(capabilities.color_depth, ColorDepth::TrueColor);
+    }
+
+    #[test]
+    fn dumb_terminals_ignore_colorterm() {
+        let capabilities = Capabilities::detect(Some("dumb"), Some("truecolor"), false, None, None);
+
+        assert_eq!(capabilities.color_depth, ColorDepth::NoColor);
+    }
+}
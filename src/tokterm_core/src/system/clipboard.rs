@@ -0,0 +1,75 @@
+/// Asks the terminal for the text of the clipboard, which is sent back as an
+/// OSC 52 reply.
+pub const CLIPBOARD_REQUEST: &str = "\x1b]52;c;?\x07";
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Gets the OSC 52 sequence copying a text to the clipboard.
+pub fn get_copy_sequence(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", encode_base64(text.as_bytes()))
+}
+
+/// Gets the text of an OSC 52 reply, given the payload between `ESC ]` and
+/// the terminator. `None` is returned for other replies, or when the
+/// terminal refused to disclose the clipboard.
+pub fn parse_reply(payload: &[u8]) -> Option<String> {
+    let mut fields = payload.splitn(3, |byte| *byte == b';');
+
+    if fields.next() != Some(b"52") {
+        return None;
+    }
+
+    let data = fields.nth(1)?;
+
+    if data == b"?" {
+        return None;
+    }
+
+    decode_base64(data).and_then(|bytes| String::from_utf8(bytes).ok())
+}
+
+/// Encodes bytes as padded base64.
+pub fn encode_base64(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let value = (chunk[0] as u32) << 16
+            | (*chunk.get(1).unwrap_or(&0) as u32) << 8
+            | *chunk.get(2).unwrap_or(&0) as u32;
+
+        for index in 0..4 {
+            if index <= chunk.len() {
+                let sextet = (value >> (18 - index * 6)) & 0x3f;
+                encoded.push(BASE64_ALPHABET[sextet as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    encoded
+}
+
+/// Decodes base64, with or without padding. `None` is returned when the
+/// data isn't valid base64.
+pub fn decode_base64(data: &[u8]) -> Option<Vec<u8>> {
+    let mut decoded = Vec::with_capacity(data.len() / 4 * 3);
+    let mut value = 0u32;
+    let mut bits = 0;
+
+    for byte in data.iter().take_while(|byte| **byte != b'=') {
+        let sextet = BASE64_ALPHABET.iter().position(|symbol| symbol == byte)?;
+
+        value = value << 6 | sextet as u32;
+        bits += 6;
+
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push((value >> bits) as u8);
+            value &= (1 << bits) - 1;
+        }
+    }
+
+    Some(decoded)
+}
@@ -0,0 +1,58 @@
+use drawing::rgb::Rgb;
+
+/// Restores the cursor style and color the user had configured.
+pub const CURSOR_RESET_SEQUENCE: &str = "\x1b[0 q\x1b]112\x07";
+
+/// Enumerates the shapes of the text cursor.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CursorShape {
+    Block,
+    Underline,
+    Bar,
+}
+
+/// The shape of the text cursor and whether it blinks.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct CursorStyle {
+    pub shape: CursorShape,
+    pub blinking: bool,
+}
+
+#[allow(dead_code)]
+impl CursorStyle {
+    pub fn new(shape: CursorShape, blinking: bool) -> CursorStyle {
+        CursorStyle { shape, blinking }
+    }
+
+    /// Gets the DECSCUSR parameter of the style, from 1 to 6.
+    pub fn get_decscusr(&self) -> u8 {
+        let steady = if self.blinking { 0 } else { 1 };
+
+        match self.shape {
+            CursorShape::Block => 1 + steady,
+            CursorShape::Underline => 3 + steady,
+            CursorShape::Bar => 5 + steady,
+        }
+    }
+}
+
+/// Gets the DECSCUSR sequence setting a cursor style, or restoring the
+/// user's one when `None` is given.
+pub fn get_style_sequence(style: Option<CursorStyle>) -> String {
+    match style {
+        Some(style) => format!("\x1b[{} q", style.get_decscusr()),
+        None => String::from("\x1b[0 q"),
+    }
+}
+
+/// Gets the OSC 12 sequence setting the cursor color, or the OSC 112 one
+/// restoring the user's color when `None` is given.
+pub fn get_color_sequence(color: Option<Rgb>) -> String {
+    match color {
+        Some(color) => format!(
+            "\x1b]12;rgb:{:02x}/{:02x}/{:02x}\x07",
+            color.red, color.green, color.blue
+        ),
+        None => String::from("\x1b]112\x07"),
+    }
+}
@@ -0,0 +1,6 @@
+/// Makes the terminal report when its window gains or loses the focus, with
+/// `FOCUS_IN` and `FOCUS_OUT`.
+pub const ENABLE_FOCUS_EVENTS: &str = "\x1b[?1004h";
+pub const DISABLE_FOCUS_EVENTS: &str = "\x1b[?1004l";
+pub const FOCUS_IN: &[u8] = b"\x1b[I";
+pub const FOCUS_OUT: &[u8] = b"\x1b[O";
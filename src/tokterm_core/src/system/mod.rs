@@ -1,2 +1,12 @@
 pub mod application;
+pub mod capabilities;
+pub mod clipboard;
+pub mod cursor;
+pub mod focus;
+pub mod paste;
+pub mod restore;
+#[cfg(unix)]
+pub mod signals;
 pub mod terminal;
+pub mod terminfo;
+pub mod title;
@@ -0,0 +1,21 @@
+/// Makes the terminal wrap pasted text between `PASTE_START` and
+/// `PASTE_END`, so it can't be mistaken for typed keys.
+pub const ENABLE_BRACKETED_PASTE: &str = "\x1b[?2004h";
+pub const DISABLE_BRACKETED_PASTE: &str = "\x1b[?2004l";
+pub const PASTE_START: &[u8] = b"\x1b[200~";
+pub const PASTE_END: &[u8] = b"\x1b[201~";
+
+/// Finds where the pasted text ends, that is the position of `PASTE_END`.
+pub fn find_paste_end(bytes: &[u8]) -> Option<usize> {
+    bytes
+        .windows(PASTE_END.len())
+        .position(|window| window == PASTE_END)
+}
+
+/// Gets the text of a paste. Terminals send line breaks as carriage
+/// returns, they are turned into `\n` like `\r\n` ones.
+pub fn get_paste_text(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes)
+        .replace("\r\n", "\n")
+        .replace('\r', "\n")
+}
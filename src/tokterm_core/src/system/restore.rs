@@ -0,0 +1,47 @@
+use std::panic;
+use std::sync::{Mutex, MutexGuard, Once};
+
+/// Restores the terminal of the running backend, like leaving raw mode and
+/// the alternate screen.
+pub type RestoreHandler = Box<Fn() + Send>;
+
+static RESTORE_HANDLER: Mutex<Option<RestoreHandler>> = Mutex::new(None);
+static INSTALL_HOOK: Once = Once::new();
+
+/// Sets the handler that restores the terminal, installing a panic hook that
+/// runs it before the panic message is printed. Backends set it when they
+/// take over the terminal and clear it once they have restored it.
+pub fn set_restore_handler(handler: RestoreHandler) {
+    INSTALL_HOOK.call_once(|| {
+        let previous_hook = panic::take_hook();
+
+        panic::set_hook(Box::new(move |info| {
+            restore_terminal();
+            previous_hook(info);
+        }));
+    });
+
+    *lock_handler() = Some(handler);
+}
+
+/// Removes the handler without running it.
+pub fn clear_restore_handler() {
+    lock_handler().take();
+}
+
+/// Runs the handler, if any, so the terminal is only restored once.
+pub fn restore_terminal() {
+    let handler = lock_handler().take();
+
+    if let Some(handler) = handler {
+        handler();
+    }
+}
+
+/// Locks the handler even when a panic happened while it was held.
+fn lock_handler() -> MutexGuard<'static, Option<RestoreHandler>> {
+    match RESTORE_HANDLER.lock() {
+        Ok(guard) => guard,
+        Err(error) => error.into_inner(),
+    }
+}
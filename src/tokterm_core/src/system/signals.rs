@@ -0,0 +1,118 @@
+use libc::{
+    c_int, cc_t, raise, sigaction, sigemptyset, sighandler_t, signal, termios, ISIG, SA_RESTART,
+    SIGCONT, SIGTSTP, SIGWINCH, SIG_DFL, VINTR, VQUIT,
+};
+use std::io;
+use std::mem::zeroed;
+use std::ptr::null_mut;
+use std::sync::atomic::{AtomicBool, Ordering};
+use Error;
+use Result;
+
+/// The value disabling a special character of the terminal.
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "dragonfly"
+))]
+const DISABLED_CHARACTER: cc_t = 0xff;
+#[cfg(not(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "dragonfly"
+)))]
+const DISABLED_CHARACTER: cc_t = 0;
+
+static WINDOW_RESIZED: AtomicBool = AtomicBool::new(false);
+static SUSPEND_REQUESTED: AtomicBool = AtomicBool::new(false);
+static RESUMED: AtomicBool = AtomicBool::new(false);
+
+/// Starts watching the `SIGWINCH` signal, sent when the terminal window is
+/// resized. Watching several times is harmless.
+pub fn watch_window_resize() -> Result<()> {
+    install_handler(SIGWINCH, on_window_resize)
+}
+
+/// Returns whether the window was resized since the last call. Resizes
+/// received in between are coalesced into one.
+#[inline]
+pub fn take_window_resize() -> bool {
+    WINDOW_RESIZED.swap(false, Ordering::SeqCst)
+}
+
+/// Starts watching job control: `SIGTSTP` asks to suspend instead of
+/// stopping right away, so the terminal can be restored first, and `SIGCONT`
+/// tells the process was continued.
+pub fn watch_suspend() -> Result<()> {
+    install_handler(SIGTSTP, on_suspend)?;
+    install_handler(SIGCONT, on_resume)
+}
+
+/// Returns whether suspending was requested since the last call.
+#[inline]
+pub fn take_suspend() -> bool {
+    SUSPEND_REQUESTED.swap(false, Ordering::SeqCst)
+}
+
+/// Returns whether the process was continued since the last call, either
+/// after `suspend_process` or after being stopped by someone else.
+#[inline]
+pub fn take_resume() -> bool {
+    RESUMED.swap(false, Ordering::SeqCst)
+}
+
+/// Stops the process like the default action of `SIGTSTP` does, returning
+/// once it is continued.
+pub fn suspend_process() -> Result<()> {
+    unsafe {
+        signal(SIGTSTP, SIG_DFL);
+        raise(SIGTSTP);
+    }
+
+    install_handler(SIGTSTP, on_suspend)
+}
+
+/// Lets the suspend key of raw mode attributes send `SIGTSTP`, while the
+/// interrupt and quit keys keep arriving as keys.
+pub fn enable_suspend_key(attributes: &mut termios) {
+    attributes.c_lflag |= ISIG;
+    attributes.c_cc[VINTR] = DISABLED_CHARACTER;
+    attributes.c_cc[VQUIT] = DISABLED_CHARACTER;
+}
+
+extern "C" fn on_window_resize(_: c_int) {
+    WINDOW_RESIZED.store(true, Ordering::SeqCst);
+}
+
+extern "C" fn on_suspend(_: c_int) {
+    SUSPEND_REQUESTED.store(true, Ordering::SeqCst);
+}
+
+extern "C" fn on_resume(_: c_int) {
+    RESUMED.store(true, Ordering::SeqCst);
+}
+
+/// Installs a signal handler, restarting the system calls it interrupts so
+/// blocking reads aren't disturbed.
+fn install_handler(signal: c_int, handler: extern "C" fn(c_int)) -> Result<()> {
+    let mut action: sigaction = unsafe { zeroed() };
+    action.sa_sigaction = handler as sighandler_t;
+    action.sa_flags = SA_RESTART;
+
+    unsafe { sigemptyset(&mut action.sa_mask) };
+
+    if unsafe { sigaction(signal, &action, null_mut()) } != 0 {
+        return Err(Error::io(
+            "Couldn't install the signal handler.",
+            io::Error::last_os_error(),
+        ));
+    }
+
+    Ok(())
+}
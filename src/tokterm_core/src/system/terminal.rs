@@ -1,6 +1,11 @@
 use drawing::cell_buffer::CellBuffer;
+use drawing::palette::Palette;
 use drawing::point_2d::Point2d;
+use drawing::rgb::Rgb;
 use drawing::size_2d::Size2d;
+use system::capabilities::Capabilities;
+use system::cursor::CursorStyle;
+use Error;
 use Result;
 
 pub trait Terminal {
@@ -10,6 +15,60 @@ pub trait Terminal {
     /// Moves the console cursor to a given position.
     fn set_cursor(&mut self, position: Point2d) -> Result<()>;
 
+    /// Sets the shape of the cursor and whether it blinks, or restores the
+    /// user's own cursor when `None` is given.
+    fn set_cursor_style(&mut self, _style: Option<CursorStyle>) -> Result<()> {
+        Err(Error::NotSupported(
+            "The terminal can not change the cursor style.",
+        ))
+    }
+
+    /// Sets the color of the cursor, or restores the user's own color when
+    /// `None` is given.
+    fn set_cursor_color(&mut self, _color: Option<Rgb>) -> Result<()> {
+        Err(Error::NotSupported(
+            "The terminal can not change the cursor color.",
+        ))
+    }
+
+    /// Sets the window title, which also labels the tab of the terminal.
+    /// The user's title is restored on exit.
+    fn set_title(&mut self, _title: &str) -> Result<()> {
+        Err(Error::NotSupported(
+            "The terminal can not change its title.",
+        ))
+    }
+
+    /// Saves the current title, so it can be restored with `pop_title`.
+    fn push_title(&mut self) -> Result<()> {
+        Err(Error::NotSupported(
+            "The terminal can not change its title.",
+        ))
+    }
+
+    /// Restores the last title saved with `push_title`.
+    fn pop_title(&mut self) -> Result<()> {
+        Err(Error::NotSupported(
+            "The terminal can not change its title.",
+        ))
+    }
+
+    /// Copies a text to the system clipboard.
+    fn copy_to_clipboard(&mut self, _text: &str) -> Result<()> {
+        Err(Error::NotSupported(
+            "The terminal can not access the clipboard.",
+        ))
+    }
+
+    /// Asks for the text of the system clipboard, which arrives later as an
+    /// `Event::Clipboard`. Terminals may ignore the request, for instance
+    /// when the user didn't allow reading the clipboard.
+    fn request_clipboard(&mut self) -> Result<()> {
+        Err(Error::NotSupported(
+            "The terminal can not access the clipboard.",
+        ))
+    }
+
     /// Gets the current console size in character units.
     fn get_console_size(&self) -> Result<Size2d>;
 
@@ -18,4 +77,12 @@ pub trait Terminal {
 
     /// Draws a `CellBuffer` to the screen.
     fn write(&mut self, cell_buffer: &mut CellBuffer) -> Result<()>;
+
+    /// Sets the palette used to present the base colors, or restores the
+    /// terminal's own colors when `None` is given.
+    fn set_palette(&mut self, palette: Option<&Palette>) -> Result<()>;
+
+    /// Gets what the terminal supports. Backends detect it once, usually with
+    /// `Capabilities::from_env`, since that reads the terminfo entry.
+    fn capabilities(&self) -> Capabilities;
 }
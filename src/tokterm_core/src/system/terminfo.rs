@@ -0,0 +1,378 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use Error;
+use Result;
+
+/// Magic number of the legacy format, with 16 bits numbers.
+const MAGIC_LEGACY: u16 = 0o432;
+/// Magic number of the extended number format, with 32 bits numbers.
+const MAGIC_32BIT: u16 = 0o1036;
+
+/// Indices of the boolean capabilities in the compiled entries.
+pub const HAS_STATUS_LINE: usize = 9;
+pub const CAN_CHANGE: usize = 27;
+
+/// Indices of the number capabilities in the compiled entries.
+pub const MAX_COLORS: usize = 13;
+
+/// Indices of the string capabilities in the compiled entries.
+pub const ENTER_BOLD_MODE: usize = 27;
+pub const ENTER_CA_MODE: usize = 28;
+pub const ENTER_DIM_MODE: usize = 30;
+pub const ENTER_REVERSE_MODE: usize = 34;
+pub const ENTER_UNDERLINE_MODE: usize = 36;
+pub const TO_STATUS_LINE: usize = 135;
+pub const ENTER_ITALICS_MODE: usize = 311;
+pub const KEY_MOUSE: usize = 355;
+
+/// A compiled terminfo entry, as found in the terminfo databases.
+#[derive(Debug, Clone, Default)]
+pub struct Terminfo {
+    pub names: Vec<String>,
+    booleans: Vec<bool>,
+    numbers: Vec<i32>,
+    strings: Vec<Option<Vec<u8>>>,
+    extended_booleans: HashMap<String, bool>,
+    extended_numbers: HashMap<String, i32>,
+    extended_strings: HashMap<String, Vec<u8>>,
+}
+
+#[allow(dead_code)]
+impl Terminfo {
+    /// Loads the entry of a terminal name from the usual terminfo
+    /// directories, `$TERMINFO`, `~/.terminfo` and `$TERMINFO_DIRS` first.
+    pub fn load(name: &str) -> Result<Terminfo> {
+        if name.is_empty() || name.contains('/') {
+            return Err(Error::InvalidArgument("Invalid terminal name."));
+        }
+
+        let first = name.as_bytes()[0];
+
+        for directory in search_directories() {
+            for subdirectory in &[(first as char).to_string(), format!("{:x}", first)] {
+                let path = directory.join(subdirectory).join(name);
+
+                match fs::read(&path) {
+                    Ok(bytes) => return Terminfo::parse(&bytes),
+                    Err(ref error) if error.kind() == io::ErrorKind::NotFound => (),
+                    Err(error) => {
+                        return Err(Error::io("Couldn't read the terminfo entry.", error))
+                    }
+                }
+            }
+        }
+
+        Err(Error::NotSupported("The terminal has no terminfo entry."))
+    }
+
+    /// Parses a compiled entry, in the legacy or the 32 bits number format,
+    /// including the extended capabilities when present.
+    pub fn parse(bytes: &[u8]) -> Result<Terminfo> {
+        let mut reader = Reader { bytes, position: 0 };
+        let number_size = match reader.u16()? {
+            MAGIC_LEGACY => 2,
+            MAGIC_32BIT => 4,
+            _ => return Err(malformed()),
+        };
+        let names_size = reader.count()?;
+        let boolean_count = reader.count()?;
+        let number_count = reader.count()?;
+        let string_count = reader.count()?;
+        let table_size = reader.count()?;
+
+        let names = String::from_utf8_lossy(reader.bytes(names_size)?);
+        let names = names
+            .trim_end_matches('\0')
+            .split('|')
+            .map(String::from)
+            .collect();
+        let booleans = reader
+            .bytes(boolean_count)?
+            .iter()
+            .map(|b| *b == 1)
+            .collect();
+
+        reader.align();
+        let numbers = reader.numbers(number_count, number_size)?;
+        let offsets = reader.offsets(string_count)?;
+        let table = reader.bytes(table_size)?;
+        let strings = offsets
+            .iter()
+            .map(|offset| offset.and_then(|offset| read_string(table, offset)))
+            .collect();
+
+        let mut terminfo = Terminfo {
+            names,
+            booleans,
+            numbers,
+            strings,
+            ..Terminfo::default()
+        };
+
+        reader.align();
+
+        if reader.remaining() >= 10 {
+            terminfo.parse_extended(&mut reader, number_size)?;
+        }
+
+        Ok(terminfo)
+    }
+
+    /// Parses the extended capabilities, whose names are stored after their
+    /// string values.
+    fn parse_extended(&mut self, reader: &mut Reader, number_size: usize) -> Result<()> {
+        let boolean_count = reader.count()?;
+        let number_count = reader.count()?;
+        let string_count = reader.count()?;
+        let _item_count = reader.count()?;
+        let table_size = reader.count()?;
+
+        let booleans: Vec<bool> = reader
+            .bytes(boolean_count)?
+            .iter()
+            .map(|b| *b == 1)
+            .collect();
+
+        reader.align();
+        let numbers = reader.numbers(number_count, number_size)?;
+        let value_offsets = reader.offsets(string_count)?;
+        let name_offsets = reader.offsets(boolean_count + number_count + string_count)?;
+        let table = reader.bytes(table_size)?;
+
+        // names are offsets from the end of the last string value.
+        let mut names_start = 0;
+
+        for offset in value_offsets.iter().filter_map(|offset| *offset) {
+            let value = table.get(offset..).ok_or_else(malformed)?;
+
+            if let Some(end) = value.iter().position(|b| *b == 0) {
+                names_start = names_start.max(offset + end + 1);
+            }
+        }
+        let mut names = name_offsets.iter().map(|offset| {
+            offset
+                .and_then(|offset| read_string(table, names_start + offset))
+                .map(|name| String::from_utf8_lossy(&name).into_owned())
+                .unwrap_or_default()
+        });
+
+        for value in booleans {
+            self.extended_booleans
+                .insert(names.next().unwrap_or_default(), value);
+        }
+
+        for value in numbers {
+            self.extended_numbers
+                .insert(names.next().unwrap_or_default(), value);
+        }
+
+        for offset in value_offsets {
+            let name = names.next().unwrap_or_default();
+
+            if let Some(value) = offset.and_then(|offset| read_string(table, offset)) {
+                self.extended_strings.insert(name, value);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Gets a boolean capability by its standard index.
+    #[inline]
+    pub fn get_flag(&self, index: usize) -> bool {
+        self.booleans.get(index).cloned().unwrap_or(false)
+    }
+
+    /// Gets a number capability by its standard index, if present.
+    pub fn get_number(&self, index: usize) -> Option<i32> {
+        match self.numbers.get(index) {
+            Some(number) if *number >= 0 => Some(*number),
+            _ => None,
+        }
+    }
+
+    /// Gets a string capability by its standard index, if present.
+    pub fn get_string(&self, index: usize) -> Option<&[u8]> {
+        match self.strings.get(index) {
+            Some(Some(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Gets an extended boolean capability, like `Tc` or `XT`.
+    #[inline]
+    pub fn get_extended_flag(&self, name: &str) -> bool {
+        self.extended_booleans.get(name).cloned().unwrap_or(false)
+    }
+
+    #[inline]
+    pub fn get_extended_number(&self, name: &str) -> Option<i32> {
+        self.extended_numbers.get(name).cloned()
+    }
+
+    #[inline]
+    pub fn get_extended_string(&self, name: &str) -> Option<&[u8]> {
+        self.extended_strings.get(name).map(|value| &value[..])
+    }
+}
+
+/// Lists the directories searched for terminfo entries, in order.
+fn search_directories() -> Vec<PathBuf> {
+    let mut directories = Vec::new();
+
+    if let Some(directory) = env::var_os("TERMINFO") {
+        directories.push(PathBuf::from(directory));
+    }
+
+    if let Some(home) = env::var_os("HOME") {
+        directories.push(PathBuf::from(home).join(".terminfo"));
+    }
+
+    if let Ok(list) = env::var("TERMINFO_DIRS") {
+        for directory in list.split(':') {
+            directories.push(PathBuf::from(if directory.is_empty() {
+                "/usr/share/terminfo"
+            } else {
+                directory
+            }));
+        }
+    }
+
+    for directory in &[
+        "/etc/terminfo",
+        "/lib/terminfo",
+        "/usr/share/terminfo",
+        "/usr/lib/terminfo",
+        "/usr/share/lib/terminfo",
+    ] {
+        directories.push(PathBuf::from(directory));
+    }
+
+    directories
+}
+
+/// Reads a NUL terminated string at an offset of a string table.
+fn read_string(table: &[u8], offset: usize) -> Option<Vec<u8>> {
+    let value = table.get(offset..)?;
+    let end = value.iter().position(|b| *b == 0)?;
+    Some(value[..end].to_vec())
+}
+
+#[inline]
+fn malformed() -> Error {
+    Error::InvalidArgument("Malformed terminfo entry.")
+}
+
+/// Reads the little endian values of a compiled entry in order.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    #[inline]
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.position
+    }
+
+    fn bytes(&mut self, count: usize) -> Result<&'a [u8]> {
+        if self.remaining() < count {
+            return Err(malformed());
+        }
+
+        let bytes = &self.bytes[self.position..self.position + count];
+        self.position += count;
+        Ok(bytes)
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        let bytes = self.bytes(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    /// Reads a section count, where `-1` stands for an empty section.
+    fn count(&mut self) -> Result<usize> {
+        match self.u16()? as i16 {
+            count if count >= 0 => Ok(count as usize),
+            -1 => Ok(0),
+            _ => Err(malformed()),
+        }
+    }
+
+    fn numbers(&mut self, count: usize, size: usize) -> Result<Vec<i32>> {
+        let bytes = self.bytes(count * size)?;
+
+        Ok(bytes
+            .chunks(size)
+            .map(|number| match size {
+                2 => i32::from(i16::from_le_bytes([number[0], number[1]])),
+                _ => i32::from_le_bytes([number[0], number[1], number[2], number[3]]),
+            })
+            .collect())
+    }
+
+    /// Reads string offsets, where negative values mark absent strings.
+    fn offsets(&mut self, count: usize) -> Result<Vec<Option<usize>>> {
+        let mut offsets = Vec::with_capacity(count);
+
+        for _ in 0..count {
+            let offset = self.u16()? as i16;
+            offsets.push(if offset >= 0 {
+                Some(offset as usize)
+            } else {
+                None
+            });
+        }
+
+        Ok(offsets)
+    }
+
+    /// Skips the padding byte placed before sections starting on odd
+    /// positions.
+    #[inline]
+    fn align(&mut self) {
+        if self.position % 2 == 1 && self.position < self.bytes.len() {
+            self.position += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds an entry named `x` with no standard capabilities and one
+    /// extended string, whose value is at `value_offset` of `table`.
+    fn get_entry(value_offset: u16, table: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        for value in &[MAGIC_LEGACY, 2, 0, 0, 0, 0] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+
+        bytes.extend_from_slice(b"x\0");
+
+        for value in &[0, 0, 1, 2, table.len() as u16, value_offset, 0] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+
+        bytes.extend_from_slice(table);
+        bytes
+    }
+
+    #[test]
+    fn extended_strings_are_read() {
+        let terminfo = Terminfo::parse(&get_entry(0, b"v\0Ms\0")).unwrap();
+
+        assert_eq!(terminfo.names, ["x"]);
+        assert_eq!(terminfo.get_extended_string("Ms"), Some(&b"v"[..]));
+    }
+
+    #[test]
+    fn extended_offsets_past_the_table_are_rejected() {
+        assert!(Terminfo::parse(&get_entry(100, b"v\0Ms\0")).is_err());
+    }
+}
@@ -0,0 +1,91 @@
+use Error;
+use Result;
+
+/// Saves the window title and icon name on the XTWINOPS title stack.
+const SAVE_TITLE: &str = "\x1b[22;0t";
+/// Restores the last window title and icon name saved on the stack.
+const RESTORE_TITLE: &str = "\x1b[23;0t";
+
+/// Keeps track of the titles set by an application, building the escape
+/// sequences that change them. The user's title is saved on the terminal's
+/// title stack before the first change, so it can be restored on exit.
+#[derive(Debug, Clone, Default)]
+pub struct TitleStack {
+    current: Option<String>,
+    titles: Vec<Option<String>>,
+    saved: bool,
+}
+
+#[allow(dead_code)]
+impl TitleStack {
+    pub fn new() -> TitleStack {
+        TitleStack::default()
+    }
+
+    /// Gets the title set by the application, `None` meaning the user's own.
+    #[inline]
+    pub fn get_title(&self) -> Option<&str> {
+        self.current.as_deref()
+    }
+
+    /// Gets the sequence setting the window title and the icon name, which
+    /// labels the tabs of most terminals.
+    pub fn set_title(&mut self, title: &str) -> String {
+        // control characters would end the sequence early.
+        let title: String = title.chars().filter(|c| !c.is_control()).collect();
+        let mut sequence = self.save_user_title();
+
+        sequence += &format!("\x1b]0;{}\x07", title);
+        self.current = Some(title);
+        sequence
+    }
+
+    /// Gets the sequence saving the current title, so it can be popped back.
+    pub fn push_title(&mut self) -> String {
+        let sequence = self.save_user_title();
+
+        self.titles.push(self.current.clone());
+        sequence + SAVE_TITLE
+    }
+
+    /// Gets the sequence restoring the last title pushed. The title is also
+    /// set again, for terminals without a title stack.
+    pub fn pop_title(&mut self) -> Result<String> {
+        let title = match self.titles.pop() {
+            Some(title) => title,
+            None => return Err(Error::InvalidOperation("There is no title to pop.")),
+        };
+        let mut sequence = String::from(RESTORE_TITLE);
+
+        if let Some(ref title) = title {
+            sequence += &format!("\x1b]0;{}\x07", title);
+        }
+
+        self.current = title;
+        Ok(sequence)
+    }
+
+    /// Gets the sequence restoring the user's title, empty when it was never
+    /// changed. The titles pushed are dropped.
+    pub fn restore(&mut self) -> String {
+        if !self.saved {
+            return String::new();
+        }
+
+        let sequence = RESTORE_TITLE.repeat(self.titles.len() + 1);
+
+        self.saved = false;
+        self.titles.clear();
+        self.current = None;
+        sequence
+    }
+
+    fn save_user_title(&mut self) -> String {
+        if self.saved {
+            return String::new();
+        }
+
+        self.saved = true;
+        String::from(SAVE_TITLE)
+    }
+}
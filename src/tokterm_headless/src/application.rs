@@ -0,0 +1,109 @@
+use std::collections::vec_deque::VecDeque;
+use terminal::HeadlessTerminal;
+use tokterm_core::drawing::point_2d::Point2d;
+use tokterm_core::drawing::size_2d::Size2d;
+use tokterm_core::events::event::{Event, WindowEvent, WindowEventType};
+use tokterm_core::events::event_queue::EventQueue;
+use tokterm_core::input::keyboard_state::KeyboardState;
+use tokterm_core::input::mouse_state::MouseState;
+use tokterm_core::system::application::Application;
+use tokterm_core::system::terminal::Terminal;
+use tokterm_core::Result;
+
+/// An application backed by a `HeadlessTerminal`. Injected events are
+/// delivered on the next `listen_events` call, like native events would be.
+pub struct HeadlessApplication {
+    terminal: HeadlessTerminal,
+    pending_events: VecDeque<Event>,
+    event_queue: EventQueue,
+    mouse_state: MouseState,
+    keyboard_state: KeyboardState,
+}
+
+impl HeadlessApplication {
+    pub fn new(size: Size2d) -> HeadlessApplication {
+        HeadlessApplication {
+            terminal: HeadlessTerminal::new(size),
+            pending_events: VecDeque::new(),
+            event_queue: EventQueue::new(),
+            mouse_state: MouseState::new(),
+            keyboard_state: KeyboardState::new(),
+        }
+    }
+
+    /// Injects an event, to be delivered on the next `listen_events` call.
+    pub fn push_event(&mut self, event: Event) {
+        self.pending_events.push_back(event);
+    }
+
+    /// Resizes the console and injects the matching `WindowResize` event.
+    pub fn resize(&mut self, size: Size2d) {
+        self.terminal.set_console_size(size);
+        self.push_event(Event::Window(WindowEvent {
+            event_type: WindowEventType::WindowResize,
+            position: Point2d::empty(),
+            size,
+        }));
+    }
+
+    #[inline]
+    pub fn get_headless_terminal(&self) -> &HeadlessTerminal {
+        &self.terminal
+    }
+
+    #[inline]
+    pub fn get_mut_headless_terminal(&mut self) -> &mut HeadlessTerminal {
+        &mut self.terminal
+    }
+}
+
+impl Application for HeadlessApplication {
+    #[inline]
+    fn get_terminal(&self) -> &Terminal {
+        &self.terminal
+    }
+
+    #[inline]
+    fn get_mut_terminal(&mut self) -> &mut Terminal {
+        &mut self.terminal
+    }
+
+    #[inline]
+    fn get_mouse_state(&self) -> &MouseState {
+        &self.mouse_state
+    }
+
+    #[inline]
+    fn get_keyboard_state(&self) -> &KeyboardState {
+        &self.keyboard_state
+    }
+
+    #[inline]
+    fn get_event_queue(&self) -> &EventQueue {
+        &self.event_queue
+    }
+
+    #[inline]
+    fn get_mut_event_queue(&mut self) -> &mut EventQueue {
+        &mut self.event_queue
+    }
+
+    fn listen_events(&mut self) -> Result<()> {
+        if self.terminal.take_clipboard_request() {
+            let text = String::from(self.terminal.get_clipboard());
+            self.pending_events.push_back(Event::Clipboard(text));
+        }
+
+        while let Some(event) = self.pending_events.pop_front() {
+            match event {
+                Event::Mouse(mouse) => self.mouse_state.update_from_event(mouse),
+                Event::Keyboard(keyboard) => self.keyboard_state.update_from_event(keyboard),
+                _ => (),
+            }
+
+            self.event_queue.add_event(event);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+extern crate tokterm_core;
+
+pub mod application;
+pub mod snapshot;
+pub mod terminal;
@@ -0,0 +1,201 @@
+use std::env;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use tokterm_core::drawing::cell_buffer::CellBuffer;
+use tokterm_core::drawing::color::Color;
+
+/// Environment variable that, when set to anything but `0`, makes
+/// `assert_snapshot` rewrite the fixtures instead of comparing them.
+pub const BLESS_VAR: &str = "TOKTERM_BLESS";
+
+const COLOR_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// Renders the characters of a `CellBuffer` as a plain-text grid, one line per
+/// row.
+pub fn render_text(cell_buffer: &CellBuffer) -> String {
+    let width = cell_buffer.size.width;
+    let mut text = String::with_capacity((width + 1) * cell_buffer.size.height);
+    let cells: Vec<_> = cell_buffer.iter().collect();
+
+    for row in cells.chunks(width.max(1)) {
+        text.extend(row.iter().map(|cell| cell.character));
+        text.push('\n');
+    }
+
+    text
+}
+
+/// Renders the colors of a `CellBuffer` as a compact grid, one line per row,
+/// with the foreground digits and the background digits separated by `|`.
+/// Each color is written as its hexadecimal index in `Color`.
+pub fn render_colors(cell_buffer: &CellBuffer) -> String {
+    let width = cell_buffer.size.width;
+    let mut text = String::with_capacity((width * 2 + 2) * cell_buffer.size.height);
+    let cells: Vec<_> = cell_buffer.iter().collect();
+
+    for row in cells.chunks(width.max(1)) {
+        text.extend(row.iter().map(|cell| color_digit(cell.foreground)));
+        text.push('|');
+        text.extend(row.iter().map(|cell| color_digit(cell.background)));
+        text.push('\n');
+    }
+
+    text
+}
+
+/// Renders the characters of a `CellBuffer`, followed by its colors when
+/// `colors` is set.
+pub fn render(cell_buffer: &CellBuffer, colors: bool) -> String {
+    let mut text = render_text(cell_buffer);
+
+    if colors {
+        text.push_str("--\n");
+        text.push_str(&render_colors(cell_buffer));
+    }
+
+    text
+}
+
+/// Compares a rendered snapshot against a fixture file, panicking with a line
+/// diff on mismatch. When `TOKTERM_BLESS` is set the fixture is written
+/// instead.
+pub fn assert_snapshot<P: AsRef<Path>>(fixture: P, actual: &str) {
+    let fixture = fixture.as_ref();
+
+    if is_blessing() {
+        if let Some(parent) = fixture.parent() {
+            fs::create_dir_all(parent)
+                .unwrap_or_else(|error| panic!("Can't create {}: {}", parent.display(), error));
+        }
+
+        fs::write(fixture, actual)
+            .unwrap_or_else(|error| panic!("Can't write {}: {}", fixture.display(), error));
+        return;
+    }
+
+    let expected = match fs::read_to_string(fixture) {
+        Ok(expected) => expected,
+        Err(ref error) if error.kind() == ErrorKind::NotFound => panic!(
+            "Snapshot {} does not exist, run with {}=1 to create it.",
+            fixture.display(),
+            BLESS_VAR
+        ),
+        Err(error) => panic!("Can't read {}: {}", fixture.display(), error),
+    };
+
+    if expected != actual {
+        panic!(
+            "Snapshot {} does not match, run with {}=1 to update it.\n{}",
+            fixture.display(),
+            BLESS_VAR,
+            diff(&expected, actual)
+        );
+    }
+}
+
+/// Renders a `CellBuffer` and compares it against a fixture file.
+pub fn assert_cell_buffer<P: AsRef<Path>>(fixture: P, cell_buffer: &CellBuffer, colors: bool) {
+    assert_snapshot(fixture, &render(cell_buffer, colors));
+}
+
+/// Builds a line diff between two texts, prefixing removed lines with `-`,
+/// added lines with `+` and unchanged lines with a space.
+pub fn diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+
+    // the longest common subsequence table, filled from the end.
+    let mut lengths = vec![vec![0usize; actual.len() + 1]; expected.len() + 1];
+
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            lengths[i][j] = if expected[i] == actual[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut text = String::new();
+    let (mut i, mut j) = (0, 0);
+
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            push_line(&mut text, ' ', expected[i]);
+            i += 1;
+            j += 1;
+        } else if i < expected.len()
+            && (j == actual.len() || lengths[i + 1][j] >= lengths[i][j + 1])
+        {
+            push_line(&mut text, '-', expected[i]);
+            i += 1;
+        } else {
+            push_line(&mut text, '+', actual[j]);
+            j += 1;
+        }
+    }
+
+    text
+}
+
+fn push_line(text: &mut String, prefix: char, line: &str) {
+    text.push(prefix);
+    text.push_str(line);
+    text.push('\n');
+}
+
+#[inline]
+fn color_digit(color: Color) -> char {
+    COLOR_DIGITS[color as usize] as char
+}
+
+fn is_blessing() -> bool {
+    match env::var(BLESS_VAR) {
+        Ok(value) => !value.is_empty() && value != "0",
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokterm_core::drawing::cell::Cell;
+    use tokterm_core::drawing::point_2d::Point2d;
+    use tokterm_core::drawing::size_2d::Size2d;
+
+    #[test]
+    fn diff_marks_equal_lines() {
+        assert_eq!(diff("a\nb\n", "a\nb\n"), " a\n b\n");
+    }
+
+    #[test]
+    fn diff_marks_changed_lines() {
+        assert_eq!(diff("a\nb\nc\n", "a\nx\nc\n"), " a\n-b\n+x\n c\n");
+    }
+
+    #[test]
+    fn diff_marks_removed_and_added_lines() {
+        assert_eq!(diff("a\nb\n", "b\nc\n"), "-a\n b\n+c\n");
+        assert_eq!(diff("", "a\n"), "+a\n");
+        assert_eq!(diff("a\n", ""), "-a\n");
+    }
+
+    #[test]
+    fn render_text_writes_one_line_per_row() {
+        let mut cell_buffer = CellBuffer::new(Cell::default('.'), Size2d::new(3, 2));
+        cell_buffer.write_str("ab", Point2d::new(1, 1), Color::White, Color::Black);
+
+        assert_eq!(render_text(&cell_buffer), "...\n.ab\n");
+    }
+
+    #[test]
+    fn render_includes_colors_on_request() {
+        let mut cell_buffer = CellBuffer::new(Cell::default(' '), Size2d::new(2, 1));
+        cell_buffer.write_str("x", Point2d::new(1, 0), Color::Red, Color::Blue);
+
+        assert_eq!(render(&cell_buffer, false), " x\n");
+        assert_eq!(render(&cell_buffer, true), " x\n--\nf1|04\n");
+    }
+}
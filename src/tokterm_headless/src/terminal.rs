@@ -0,0 +1,261 @@
+use tokterm_core::drawing::cell_buffer::CellBuffer;
+use tokterm_core::drawing::palette::Palette;
+use tokterm_core::drawing::point_2d::Point2d;
+use tokterm_core::drawing::rgb::Rgb;
+use tokterm_core::drawing::size_2d::Size2d;
+use tokterm_core::system::capabilities::{Capabilities, ColorDepth};
+use tokterm_core::system::cursor::CursorStyle;
+use tokterm_core::system::terminal::Terminal;
+use tokterm_core::system::title::TitleStack;
+use tokterm_core::Error;
+use tokterm_core::Result;
+
+/// A terminal that keeps everything in memory, for testing applications
+/// without a TTY.
+#[derive(Debug)]
+pub struct HeadlessTerminal {
+    size: Size2d,
+    cursor: Point2d,
+    cursor_visible: bool,
+    cursor_style: Option<CursorStyle>,
+    cursor_color: Option<Rgb>,
+    titles: TitleStack,
+    clipboard: String,
+    clipboard_requested: bool,
+    palette: Option<Palette>,
+    last_buffer: Option<CellBuffer>,
+    frames: usize,
+    capabilities: Capabilities,
+}
+
+impl HeadlessTerminal {
+    /// Creates a terminal with true color, able to change its title, palette
+    /// and clipboard, until told otherwise with `set_capabilities`.
+    pub fn new(size: Size2d) -> HeadlessTerminal {
+        HeadlessTerminal {
+            size,
+            cursor: Point2d::empty(),
+            cursor_visible: true,
+            cursor_style: None,
+            cursor_color: None,
+            titles: TitleStack::new(),
+            clipboard: String::new(),
+            clipboard_requested: false,
+            palette: None,
+            last_buffer: None,
+            frames: 0,
+            capabilities: Capabilities {
+                color_depth: ColorDepth::TrueColor,
+                title: true,
+                change_palette: true,
+                clipboard: true,
+                ..Capabilities::basic()
+            },
+        }
+    }
+
+    #[inline]
+    pub fn set_console_size(&mut self, size: Size2d) {
+        self.size = size;
+    }
+
+    /// Gets the last `CellBuffer` written to the terminal.
+    #[inline]
+    pub fn get_last_buffer(&self) -> Option<&CellBuffer> {
+        self.last_buffer.as_ref()
+    }
+
+    /// Gets the amount of buffers written to the terminal.
+    #[inline]
+    pub fn get_frame_count(&self) -> usize {
+        self.frames
+    }
+
+    /// Sets the capabilities reported to the application, to test how it
+    /// degrades.
+    #[inline]
+    pub fn set_capabilities(&mut self, capabilities: Capabilities) {
+        self.capabilities = capabilities;
+    }
+
+    #[inline]
+    pub fn get_cursor(&self) -> Point2d {
+        self.cursor
+    }
+
+    #[inline]
+    pub fn is_cursor_visible(&self) -> bool {
+        self.cursor_visible
+    }
+
+    /// Gets the cursor style set by the application, `None` meaning the
+    /// user's own.
+    #[inline]
+    pub fn get_cursor_style(&self) -> Option<CursorStyle> {
+        self.cursor_style
+    }
+
+    #[inline]
+    pub fn get_cursor_color(&self) -> Option<Rgb> {
+        self.cursor_color
+    }
+
+    /// Gets the title set by the application, `None` meaning the user's own.
+    #[inline]
+    pub fn get_title(&self) -> Option<&str> {
+        self.titles.get_title()
+    }
+
+    /// Sets the text of the clipboard, like the user copying something.
+    #[inline]
+    pub fn set_clipboard(&mut self, text: &str) {
+        self.clipboard = String::from(text);
+    }
+
+    #[inline]
+    pub fn get_clipboard(&self) -> &str {
+        &self.clipboard
+    }
+
+    /// Returns whether the clipboard was requested since the last call.
+    pub fn take_clipboard_request(&mut self) -> bool {
+        let requested = self.clipboard_requested;
+
+        self.clipboard_requested = false;
+        requested
+    }
+
+    #[inline]
+    pub fn get_palette(&self) -> Option<&Palette> {
+        self.palette.as_ref()
+    }
+}
+
+impl Terminal for HeadlessTerminal {
+    /// Shows or hides the cursor.
+    fn set_cursor_visibility(&mut self, visible: bool) -> Result<()> {
+        self.cursor_visible = visible;
+        Ok(())
+    }
+
+    /// Moves the console cursor to a given position.
+    fn set_cursor(&mut self, position: Point2d) -> Result<()> {
+        self.cursor = position;
+        Ok(())
+    }
+
+    /// Sets the shape of the cursor and whether it blinks, or restores the
+    /// user's own cursor when `None` is given.
+    fn set_cursor_style(&mut self, style: Option<CursorStyle>) -> Result<()> {
+        self.cursor_style = style;
+        Ok(())
+    }
+
+    /// Sets the color of the cursor, or restores the user's own color when
+    /// `None` is given.
+    fn set_cursor_color(&mut self, color: Option<Rgb>) -> Result<()> {
+        self.cursor_color = color;
+        Ok(())
+    }
+
+    /// Sets the window title, which also labels the tab of the terminal.
+    /// The user's title is restored on exit.
+    fn set_title(&mut self, title: &str) -> Result<()> {
+        if !self.capabilities.title {
+            return Err(Error::NotSupported(
+                "The terminal can not change its title.",
+            ));
+        }
+
+        self.titles.set_title(title);
+        Ok(())
+    }
+
+    /// Saves the current title, so it can be restored with `pop_title`.
+    fn push_title(&mut self) -> Result<()> {
+        if !self.capabilities.title {
+            return Err(Error::NotSupported(
+                "The terminal can not change its title.",
+            ));
+        }
+
+        self.titles.push_title();
+        Ok(())
+    }
+
+    /// Restores the last title saved with `push_title`.
+    fn pop_title(&mut self) -> Result<()> {
+        self.titles.pop_title()?;
+        Ok(())
+    }
+
+    /// Copies a text to the system clipboard.
+    fn copy_to_clipboard(&mut self, text: &str) -> Result<()> {
+        if !self.capabilities.clipboard {
+            return Err(Error::NotSupported(
+                "The terminal can not access the clipboard.",
+            ));
+        }
+
+        self.set_clipboard(text);
+        Ok(())
+    }
+
+    /// Asks for the text of the system clipboard, which arrives later as an
+    /// `Event::Clipboard`. Terminals may ignore the request, for instance
+    /// when the user didn't allow reading the clipboard.
+    fn request_clipboard(&mut self) -> Result<()> {
+        if !self.capabilities.clipboard {
+            return Err(Error::NotSupported(
+                "The terminal can not access the clipboard.",
+            ));
+        }
+
+        self.clipboard_requested = true;
+        Ok(())
+    }
+
+    /// Gets the current console size in character units.
+    fn get_console_size(&self) -> Result<Size2d> {
+        Ok(self.size)
+    }
+
+    /// Clears the console screen.
+    fn clear(&mut self) -> Result<()> {
+        self.last_buffer = None;
+        Ok(())
+    }
+
+    /// Draws a `CellBuffer` to the screen.
+    fn write(&mut self, cell_buffer: &mut CellBuffer) -> Result<()> {
+        self.last_buffer = Some(cell_buffer.clone());
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// Sets the palette used to present the base colors, or restores the
+    /// terminal's own colors when `None` is given.
+    fn set_palette(&mut self, palette: Option<&Palette>) -> Result<()> {
+        // like the real backends, a palette needs either true color or a
+        // terminal that can change its own colors.
+        let capabilities = &self.capabilities;
+        let supported =
+            capabilities.change_palette || capabilities.color_depth == ColorDepth::TrueColor;
+
+        if palette.is_some() && !supported {
+            return Err(Error::NotSupported(
+                "The terminal can not change its colors.",
+            ));
+        }
+
+        self.palette = palette.cloned();
+        Ok(())
+    }
+
+    /// Gets what the terminal supports, detected from the environment unless
+    /// the backend knows better.
+    #[inline]
+    fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+}
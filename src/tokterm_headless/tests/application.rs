@@ -0,0 +1,180 @@
+extern crate tokterm_core;
+extern crate tokterm_headless;
+
+use tokterm_core::drawing::cell::Cell;
+use tokterm_core::drawing::cell_buffer::CellBuffer;
+use tokterm_core::drawing::color::Color;
+use tokterm_core::drawing::palette::Palette;
+use tokterm_core::drawing::point_2d::Point2d;
+use tokterm_core::drawing::size_2d::Size2d;
+use tokterm_core::events::event::{
+    Event, KeyboardEvent, KeyboardEventType, WindowEvent, WindowEventType,
+};
+use tokterm_core::input::key::Key;
+use tokterm_core::system::application::Application;
+use tokterm_core::system::capabilities::Capabilities;
+use tokterm_core::Error;
+use tokterm_headless::application::HeadlessApplication;
+use tokterm_headless::snapshot::render_text;
+
+fn get_key_event(event_type: KeyboardEventType, key: Key, character: char) -> Event {
+    Event::Keyboard(KeyboardEvent {
+        event_type,
+        key,
+        key_code: character as u16,
+        character,
+        left_control: false,
+        left_shift: false,
+        left_menu: false,
+        right_control: false,
+        right_shift: false,
+        right_menu: false,
+    })
+}
+
+#[test]
+fn injected_events_are_delivered_on_listen() {
+    let mut application = HeadlessApplication::new(Size2d::new(10, 3));
+    let key_down = get_key_event(KeyboardEventType::KeyDown, Key::Q, 'q');
+
+    application.push_event(key_down.clone());
+    assert_eq!(application.get_mut_event_queue().get_event(), None);
+
+    application.listen_events().unwrap();
+    assert_eq!(
+        application.get_mut_event_queue().get_event(),
+        Some(key_down)
+    );
+    assert_eq!(application.get_mut_event_queue().get_event(), None);
+    assert!(application.get_keyboard_state().keys[Key::Q.to_u32() as usize]);
+
+    application.push_event(get_key_event(KeyboardEventType::KeyUp, Key::Q, 'q'));
+    application.listen_events().unwrap();
+    assert!(!application.get_keyboard_state().keys[Key::Q.to_u32() as usize]);
+}
+
+#[test]
+fn resize_changes_the_size_and_reports_it() {
+    let mut application = HeadlessApplication::new(Size2d::new(10, 3));
+
+    application.resize(Size2d::new(20, 5));
+    application.listen_events().unwrap();
+
+    assert_eq!(
+        application.get_terminal().get_console_size().unwrap(),
+        Size2d::new(20, 5)
+    );
+    assert_eq!(
+        application.get_mut_event_queue().get_event(),
+        Some(Event::Window(WindowEvent {
+            event_type: WindowEventType::WindowResize,
+            position: Point2d::empty(),
+            size: Size2d::new(20, 5),
+        }))
+    );
+}
+
+#[test]
+fn written_buffers_are_captured() {
+    let mut application = HeadlessApplication::new(Size2d::new(5, 2));
+    let mut buffer = CellBuffer::new(Cell::default(' '), Size2d::new(5, 2));
+
+    buffer.write_str("hello", Point2d::new(0, 1), Color::White, Color::Blue);
+    application.get_mut_terminal().write(&mut buffer).unwrap();
+
+    let terminal = application.get_headless_terminal();
+    let captured = terminal.get_last_buffer().unwrap();
+
+    assert_eq!(terminal.get_frame_count(), 1);
+    assert_eq!(render_text(captured), "     \nhello\n");
+    assert_eq!(
+        captured.get(Point2d::new(1, 1)),
+        Some(Cell::new('e', Color::White, Color::Blue))
+    );
+}
+
+#[test]
+fn terminal_state_is_kept() {
+    let mut application = HeadlessApplication::new(Size2d::new(10, 3));
+
+    {
+        let terminal = application.get_mut_terminal();
+        terminal.set_cursor(Point2d::new(3, 2)).unwrap();
+        terminal.set_cursor_visibility(false).unwrap();
+        terminal.set_title("first").unwrap();
+        terminal.push_title().unwrap();
+        terminal.set_title("second").unwrap();
+        terminal
+            .set_palette(Some(&Palette::solarized_dark()))
+            .unwrap();
+    }
+
+    {
+        let terminal = application.get_headless_terminal();
+        assert_eq!(terminal.get_cursor(), Point2d::new(3, 2));
+        assert!(!terminal.is_cursor_visible());
+        assert_eq!(terminal.get_title(), Some("second"));
+        assert_eq!(terminal.get_palette(), Some(&Palette::solarized_dark()));
+    }
+
+    application.get_mut_terminal().pop_title().unwrap();
+    application.get_mut_terminal().set_palette(None).unwrap();
+
+    let terminal = application.get_headless_terminal();
+    assert_eq!(terminal.get_title(), Some("first"));
+    assert_eq!(terminal.get_palette(), None);
+}
+
+#[test]
+fn clipboard_requests_are_answered_on_listen() {
+    let mut application = HeadlessApplication::new(Size2d::new(10, 3));
+
+    application
+        .get_mut_headless_terminal()
+        .set_clipboard("copied");
+    application.get_mut_terminal().request_clipboard().unwrap();
+    application.listen_events().unwrap();
+
+    assert_eq!(
+        application.get_mut_event_queue().get_event(),
+        Some(Event::Clipboard(String::from("copied")))
+    );
+}
+
+#[test]
+fn unsupported_features_are_refused() {
+    let mut application = HeadlessApplication::new(Size2d::new(10, 3));
+
+    application
+        .get_mut_headless_terminal()
+        .set_capabilities(Capabilities::basic());
+
+    {
+        let terminal = application.get_mut_terminal();
+        let results = vec![
+            terminal.set_title("title"),
+            terminal.push_title(),
+            terminal.copy_to_clipboard("copied"),
+            terminal.request_clipboard(),
+            terminal.set_palette(Some(&Palette::solarized_dark())),
+        ];
+
+        for result in results {
+            match result {
+                Err(Error::NotSupported(_)) => (),
+                result => panic!("unexpected result: {:?}", result),
+            }
+        }
+
+        // the own colors can always be restored.
+        terminal.set_palette(None).unwrap();
+    }
+
+    application.listen_events().unwrap();
+    assert_eq!(application.get_mut_event_queue().get_event(), None);
+
+    let terminal = application.get_headless_terminal();
+    assert_eq!(terminal.get_title(), None);
+    assert_eq!(terminal.get_clipboard(), "");
+    assert_eq!(terminal.get_palette(), None);
+}
@@ -0,0 +1,49 @@
+extern crate tokterm_core;
+extern crate tokterm_headless;
+
+use std::path::PathBuf;
+use tokterm_core::drawing::canvas::{Canvas, SolidPaint};
+use tokterm_core::drawing::cell::Cell;
+use tokterm_core::drawing::cell_buffer::CellBuffer;
+use tokterm_core::drawing::color::Color;
+use tokterm_core::drawing::point_2d::Point2d;
+use tokterm_core::drawing::size_2d::Size2d;
+use tokterm_headless::snapshot::assert_cell_buffer;
+
+fn get_fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("snapshots")
+        .join(name)
+}
+
+#[test]
+fn stroke_ellipse() {
+    let mut cell_buffer = CellBuffer::new(Cell::default(' '), Size2d::new(24, 12));
+    let stroke = SolidPaint::new(Cell::new('#', Color::Yellow, Color::Black));
+
+    {
+        let mut canvas = Canvas::new(&mut cell_buffer, Some(&stroke), None);
+        canvas
+            .stroke_ellipse(Point2d::new(1, 1), Size2d::new(22, 10))
+            .unwrap();
+    }
+
+    assert_cell_buffer(get_fixture("stroke_ellipse.txt"), &cell_buffer, true);
+}
+
+#[test]
+fn bezier_to() {
+    let mut cell_buffer = CellBuffer::new(Cell::default(' '), Size2d::new(24, 12));
+    let stroke = SolidPaint::new(Cell::new('*', Color::Cyan, Color::Black));
+
+    {
+        let mut canvas = Canvas::new(&mut cell_buffer, Some(&stroke), None);
+        canvas.move_to(Point2d::new(0, 11));
+        canvas
+            .bezier_to(Point2d::new(23, 11), Point2d::new(12, 0))
+            .unwrap();
+    }
+
+    assert_cell_buffer(get_fixture("bezier_to.txt"), &cell_buffer, true);
+}
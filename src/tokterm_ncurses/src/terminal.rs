@@ -1,12 +1,17 @@
 use color::color_to_i16;
 use color::ColorPair;
 use ncurses::attron;
+use ncurses::can_change_color;
 use ncurses::chtype;
 use ncurses::clear;
+use ncurses::clearok;
+use ncurses::color_content;
 use ncurses::constants::ERR;
 use ncurses::curs_set;
 use ncurses::endwin;
 use ncurses::getmaxyx;
+use ncurses::has_colors;
+use ncurses::init_color;
 use ncurses::init_pair;
 use ncurses::initscr;
 use ncurses::mvwaddch;
@@ -18,35 +23,202 @@ use ncurses::COLOR_PAIR;
 use ncurses::CURSOR_VISIBILITY;
 use ncurses::WINDOW;
 use std::collections::HashMap;
+use std::io::{stdout, Write};
 use tokterm_core::drawing::cell_buffer::CellBuffer;
+use tokterm_core::drawing::color::Color;
+use tokterm_core::drawing::palette::Palette;
 use tokterm_core::drawing::point_2d::Point2d;
+use tokterm_core::drawing::rgb::Rgb;
 use tokterm_core::drawing::size_2d::Size2d;
+use tokterm_core::system::capabilities::{Capabilities, ColorDepth};
+use tokterm_core::system::clipboard::{get_copy_sequence, CLIPBOARD_REQUEST};
+use tokterm_core::system::cursor::{
+    get_color_sequence, get_style_sequence, CursorStyle, CURSOR_RESET_SEQUENCE,
+};
+use tokterm_core::system::restore::{clear_restore_handler, set_restore_handler};
+use tokterm_core::system::signals::watch_suspend;
 use tokterm_core::system::terminal::Terminal;
+use tokterm_core::system::title::TitleStack;
+use tokterm_core::Error;
 use tokterm_core::Result;
 
+/// Disables the mouse and focus reports, bracketed paste and the kitty
+/// keyboard protocol, which ncurses doesn't do when leaving.
+const RESET_SEQUENCE: &str = "\x1b[?1006l\x1b[?1003l\x1b[?1004l\x1b[?2004l\x1b[<u";
+
 pub struct NCursesTerminal {
     window: WINDOW,
+    palette: Option<Palette>,
+    original_colors: Option<Vec<(i16, i16, i16)>>,
+    capabilities: Capabilities,
+    cursor_style: Option<CursorStyle>,
+    cursor_color: Option<Rgb>,
+    titles: TitleStack,
 }
 
 impl NCursesTerminal {
+    /// Initializes ncurses, which also enters the alternate screen when the
+    /// terminfo entry has one. The terminal is restored when dropped, or
+    /// before the message of a panic is printed.
     pub fn create() -> Result<NCursesTerminal> {
         let window = initscr();
         setlocale(LcCategory::all, "");
-        Ok(NCursesTerminal { window })
+        set_restore_handler(Box::new(|| {
+            restore();
+            let _ = write_sequence(CURSOR_RESET_SEQUENCE);
+        }));
+
+        // replaces the handler of ncurses, which would stop the process
+        // without disabling the mouse reports.
+        watch_suspend()?;
+
+        // ncurses knows better than the environment whether colors work.
+        let mut capabilities = Capabilities::from_env();
+
+        if !has_colors() {
+            capabilities.color_depth = ColorDepth::NoColor;
+        }
+
+        capabilities.change_palette = can_change_color();
+
+        Ok(NCursesTerminal {
+            window,
+            palette: None,
+            original_colors: None,
+            capabilities,
+            cursor_style: None,
+            cursor_color: None,
+            titles: TitleStack::new(),
+        })
     }
 
     #[inline]
     pub fn get_window(&self) -> WINDOW {
         self.window
     }
+
+    /// Gives the terminal back to the shell before the process is stopped,
+    /// leaving ncurses until it is resumed.
+    pub fn suspend(&mut self) -> Result<()> {
+        restore();
+        self.restore_cursor()
+    }
+
+    /// Takes the terminal again once the process is continued, making the
+    /// next write draw the whole screen.
+    pub fn resume(&mut self) -> Result<()> {
+        refresh();
+
+        if clearok(self.window, true) == ERR {
+            return Err(Error::Native("Couldn't force the screen redraw.", ERR));
+        }
+
+        self.apply_cursor()
+    }
+
+    /// Restores the user's cursor, keeping the style and color to apply them
+    /// again when resumed.
+    fn restore_cursor(&mut self) -> Result<()> {
+        if self.cursor_style.is_some() {
+            write_sequence(&get_style_sequence(None))?;
+        }
+
+        if self.cursor_color.is_some() {
+            write_sequence(&get_color_sequence(None))?;
+        }
+
+        Ok(())
+    }
+
+    fn apply_cursor(&mut self) -> Result<()> {
+        if let Some(style) = self.cursor_style {
+            write_sequence(&get_style_sequence(Some(style)))?;
+        }
+
+        if let Some(color) = self.cursor_color {
+            write_sequence(&get_color_sequence(Some(color)))?;
+        }
+
+        Ok(())
+    }
+
+    /// Redefines the terminal colors with the current palette, saving the
+    /// original colors the first time so they can be restored later.
+    fn apply_palette(&mut self) -> Result<()> {
+        if self.original_colors.is_none() {
+            let mut original_colors = vec![(0, 0, 0); 16];
+
+            for color in Color::to_vec() {
+                let (mut r, mut g, mut b) = (0, 0, 0);
+                color_content(color_to_i16(color), &mut r, &mut g, &mut b);
+                original_colors[color_to_i16(color) as usize] = (r, g, b);
+            }
+
+            self.original_colors = Some(original_colors);
+        }
+
+        for color in Color::to_vec() {
+            let index = color_to_i16(color);
+            let (r, g, b) = match self.palette {
+                Some(ref palette) => {
+                    let rgb = palette.get(color);
+                    (
+                        (rgb.red as i32 * 1000 / 255) as i16,
+                        (rgb.green as i32 * 1000 / 255) as i16,
+                        (rgb.blue as i32 * 1000 / 255) as i16,
+                    )
+                }
+                None => match self.original_colors {
+                    Some(ref original_colors) => original_colors[index as usize],
+                    None => return Ok(()),
+                },
+            };
+
+            if init_color(index, r, g, b) == ERR {
+                return Err(Error::Native("Couldn't change the terminal colors.", ERR));
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl Drop for NCursesTerminal {
     fn drop(&mut self) {
-        endwin();
+        if self.original_colors.is_some() {
+            self.palette = None;
+            let _ = self.apply_palette();
+        }
+
+        curs_set(CURSOR_VISIBILITY::CURSOR_VISIBLE);
+        clear_restore_handler();
+        restore();
+        let _ = self.restore_cursor();
+        let _ = write_sequence(&self.titles.restore());
     }
 }
 
+/// Leaves ncurses, going back to cooked mode and the main screen.
+fn restore() {
+    endwin();
+    let _ = write_sequence(RESET_SEQUENCE);
+}
+
+/// Writes an escape sequence ncurses has no function for, right away.
+pub fn write_sequence(sequence: &str) -> Result<()> {
+    let mut output = stdout();
+
+    if let Err(error) = output.write_all(sequence.as_bytes()) {
+        return Err(Error::io("Couldn't write to the terminal.", error));
+    }
+
+    if let Err(error) = output.flush() {
+        return Err(Error::io("Couldn't flush the buffer.", error));
+    }
+
+    Ok(())
+}
+
 impl Terminal for NCursesTerminal {
     /// Shows or hides the cursor.
     fn set_cursor_visibility(&mut self, visible: bool) -> Result<()> {
@@ -55,7 +227,9 @@ impl Terminal for NCursesTerminal {
         } else {
             CURSOR_VISIBILITY::CURSOR_INVISIBLE
         }) {
-            None => Err("Couldn't change the cursor visibility."),
+            None => Err(Error::NotSupported(
+                "Couldn't change the cursor visibility.",
+            )),
             Some(_) => Ok(()),
         }
     }
@@ -65,10 +239,81 @@ impl Terminal for NCursesTerminal {
         if wmove(self.window, position.x as i32, position.y as i32) != ERR {
             Ok(())
         } else {
-            Err("Couldn't set the cursor position.")
+            Err(Error::Native("Couldn't set the cursor position.", ERR))
         }
     }
 
+    /// Sets the shape of the cursor and whether it blinks, or restores the
+    /// user's own cursor when `None` is given.
+    fn set_cursor_style(&mut self, style: Option<CursorStyle>) -> Result<()> {
+        write_sequence(&get_style_sequence(style))?;
+        self.cursor_style = style;
+        Ok(())
+    }
+
+    /// Sets the color of the cursor, or restores the user's own color when
+    /// `None` is given.
+    fn set_cursor_color(&mut self, color: Option<Rgb>) -> Result<()> {
+        write_sequence(&get_color_sequence(color))?;
+        self.cursor_color = color;
+        Ok(())
+    }
+
+    /// Sets the window title, which also labels the tab of the terminal.
+    /// The user's title is restored on exit.
+    fn set_title(&mut self, title: &str) -> Result<()> {
+        if !self.capabilities.title {
+            return Err(Error::NotSupported(
+                "The terminal can not change its title.",
+            ));
+        }
+
+        let sequence = self.titles.set_title(title);
+        write_sequence(&sequence)
+    }
+
+    /// Saves the current title, so it can be restored with `pop_title`.
+    fn push_title(&mut self) -> Result<()> {
+        if !self.capabilities.title {
+            return Err(Error::NotSupported(
+                "The terminal can not change its title.",
+            ));
+        }
+
+        let sequence = self.titles.push_title();
+        write_sequence(&sequence)
+    }
+
+    /// Restores the last title saved with `push_title`.
+    fn pop_title(&mut self) -> Result<()> {
+        let sequence = self.titles.pop_title()?;
+        write_sequence(&sequence)
+    }
+
+    /// Copies a text to the system clipboard.
+    fn copy_to_clipboard(&mut self, text: &str) -> Result<()> {
+        if !self.capabilities.clipboard {
+            return Err(Error::NotSupported(
+                "The terminal can not access the clipboard.",
+            ));
+        }
+
+        write_sequence(&get_copy_sequence(text))
+    }
+
+    /// Asks for the text of the system clipboard, which arrives later as an
+    /// `Event::Clipboard`. Terminals may ignore the request, for instance
+    /// when the user didn't allow reading the clipboard.
+    fn request_clipboard(&mut self) -> Result<()> {
+        if !self.capabilities.clipboard {
+            return Err(Error::NotSupported(
+                "The terminal can not access the clipboard.",
+            ));
+        }
+
+        write_sequence(CLIPBOARD_REQUEST)
+    }
+
     /// Gets the current console size in character units.
     fn get_console_size(&self) -> Result<Size2d> {
         let mut x = 0;
@@ -84,7 +329,7 @@ impl Terminal for NCursesTerminal {
         if clear() != ERR {
             Ok(())
         } else {
-            Err("Couldn't clear the screen.")
+            Err(Error::Native("Couldn't clear the screen.", ERR))
         }
     }
 
@@ -99,21 +344,25 @@ impl Terminal for NCursesTerminal {
             let position = match cell_buffer.coordinates_of(index) {
                 Some(point) => point,
                 None => {
-                    return Err("The index of the character was outside the bounds of the buffer.")
+                    return Err(Error::InvalidOperation(
+                        "The index of the character was outside the bounds of the buffer.",
+                    ))
                 }
             };
 
-            if !colors.contains_key(&color_pair) {
-                init_pair(
-                    pair_index,
-                    color_to_i16(color_pair.foreground),
-                    color_to_i16(color_pair.background),
-                );
-                colors.insert(color_pair, pair_index);
-                pair_index += 1;
-            }
+            if self.capabilities.has_color() {
+                if !colors.contains_key(&color_pair) {
+                    init_pair(
+                        pair_index,
+                        color_to_i16(color_pair.foreground),
+                        color_to_i16(color_pair.background),
+                    );
+                    colors.insert(color_pair, pair_index);
+                    pair_index += 1;
+                }
 
-            attron(COLOR_PAIR(*colors.get(&color_pair).unwrap()));
+                attron(COLOR_PAIR(*colors.get(&color_pair).unwrap()));
+            }
             mvwaddch(
                 self.window,
                 position.y,
@@ -127,4 +376,29 @@ impl Terminal for NCursesTerminal {
         refresh();
         Ok(())
     }
+
+    /// Sets the palette used to present the base colors, or restores the
+    /// terminal's own colors when `None` is given.
+    fn set_palette(&mut self, palette: Option<&Palette>) -> Result<()> {
+        if palette.is_some() && !self.capabilities.change_palette {
+            return Err(Error::NotSupported(
+                "The terminal can not change its colors.",
+            ));
+        }
+
+        // without a palette set, the own colors are still there.
+        if palette.is_none() && self.palette.is_none() {
+            return Ok(());
+        }
+
+        self.palette = palette.cloned();
+        self.apply_palette()
+    }
+
+    /// Gets what the terminal supports, detected from the environment unless
+    /// the backend knows better.
+    #[inline]
+    fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
 }
@@ -0,0 +1,181 @@
+use protocol::{
+    is_console_size, read_server_message, write_client_message, ClientMessage, ServerMessage,
+};
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::thread;
+use std::time::Duration;
+use tokterm_core::drawing::cell::Cell;
+use tokterm_core::drawing::cell_buffer::CellBuffer;
+use tokterm_core::drawing::point_2d::Point2d;
+use tokterm_core::drawing::size_2d::Size2d;
+use tokterm_core::events::event::{Event, KeyboardEvent};
+use tokterm_core::input::key::Key;
+use tokterm_core::system::application::Application;
+use tokterm_core::Error;
+use tokterm_core::Result;
+
+/// A thin client presenting a session on a local application and
+/// forwarding its size and input. Ctrl+\ detaches it.
+pub struct SessionClient {
+    stream: UnixStream,
+    input: Receiver<Result<Option<ServerMessage>>>,
+    frame: CellBuffer,
+    size: Size2d,
+    cursor: Point2d,
+    cursor_visible: bool,
+}
+
+impl SessionClient {
+    /// Connects to the session listening on a socket path.
+    pub fn attach<P: AsRef<Path>>(path: P) -> Result<SessionClient> {
+        let stream = match UnixStream::connect(path) {
+            Ok(stream) => stream,
+            Err(error) => return Err(Error::io("Couldn't connect to the session.", error)),
+        };
+        let reader = match stream.try_clone() {
+            Ok(reader) => reader,
+            Err(error) => return Err(Error::io("Couldn't clone the connection.", error)),
+        };
+
+        Ok(SessionClient {
+            stream,
+            input: spawn_reader(reader),
+            frame: CellBuffer::new(Cell::default(' '), Size2d::empty()),
+            size: Size2d::empty(),
+            cursor: Point2d::empty(),
+            cursor_visible: true,
+        })
+    }
+
+    /// Presents the session on an application until the client detaches or
+    /// the session ends.
+    pub fn run(&mut self, application: &mut Application) -> Result<()> {
+        application.get_mut_terminal().clear()?;
+
+        loop {
+            let size = application.get_terminal().get_console_size()?;
+
+            // the server refuses sizes no console could have.
+            if size != self.size && is_console_size(size) {
+                self.size = size;
+                self.send(&ClientMessage::Resize(size))?;
+            }
+
+            application.listen_events()?;
+
+            while let Some(event) = application.get_mut_event_queue().get_event() {
+                if let Event::Keyboard(keyboard) = event {
+                    if is_detach_key(&keyboard) {
+                        return self.detach();
+                    }
+                }
+
+                self.send(&ClientMessage::Event(event))?;
+            }
+
+            if !self.receive(application)? {
+                return Ok(());
+            }
+
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    /// Tells the session the client is leaving, the application keeps
+    /// running.
+    pub fn detach(&mut self) -> Result<()> {
+        self.send(&ClientMessage::Detach)
+    }
+
+    #[inline]
+    fn send(&mut self, message: &ClientMessage) -> Result<()> {
+        write_client_message(&mut self.stream, message)
+    }
+
+    /// Applies the pending server messages, presenting the frame when it
+    /// changed. Returns `false` when the session ended.
+    fn receive(&mut self, application: &mut Application) -> Result<bool> {
+        let mut redraw = false;
+
+        loop {
+            let message = match self.input.try_recv() {
+                Ok(Ok(Some(message))) => message,
+                Ok(Err(error)) => return Err(error),
+                Err(TryRecvError::Empty) => break,
+                Ok(Ok(None)) | Err(TryRecvError::Disconnected) => return Ok(false),
+            };
+
+            match message {
+                ServerMessage::Frame { size, full, runs } => {
+                    // frames no console could show are dropped instead of
+                    // allocated.
+                    if !is_console_size(size) {
+                        continue;
+                    }
+
+                    if full || size != self.frame.size {
+                        self.frame = CellBuffer::new(Cell::default(' '), size);
+                    }
+
+                    for run in runs {
+                        for (offset, cell) in run.cells.into_iter().enumerate() {
+                            let position = run
+                                .index
+                                .checked_add(offset)
+                                .and_then(|index| self.frame.coordinates_of(index));
+
+                            if let Some(position) = position {
+                                self.frame.set(position, cell);
+                            }
+                        }
+                    }
+
+                    redraw = true;
+                }
+                ServerMessage::Cursor { position, visible } => {
+                    self.cursor = position;
+                    self.cursor_visible = visible;
+                    redraw = true;
+                }
+                ServerMessage::Close => return Ok(false),
+            }
+        }
+
+        if redraw {
+            let terminal = application.get_mut_terminal();
+
+            terminal.write(&mut self.frame)?;
+            terminal.set_cursor(self.cursor)?;
+            terminal.set_cursor_visibility(self.cursor_visible)?;
+        }
+
+        Ok(true)
+    }
+}
+
+/// Checks for Ctrl+\, the detach key. Both its press and its release detach,
+/// since some backends only report the release, and the first one ends the
+/// session anyway.
+#[inline]
+fn is_detach_key(keyboard: &KeyboardEvent) -> bool {
+    keyboard.key == Key::Oem5 && (keyboard.left_control || keyboard.right_control)
+}
+
+/// Reads the server messages on their own thread, until the server
+/// disconnects or sends a malformed message.
+fn spawn_reader(mut stream: UnixStream) -> Receiver<Result<Option<ServerMessage>>> {
+    let (sender, receiver) = channel();
+
+    thread::spawn(move || loop {
+        let message = read_server_message(&mut stream);
+        let done = !matches!(message, Ok(Some(_)));
+
+        if sender.send(message).is_err() || done {
+            return;
+        }
+    });
+
+    receiver
+}
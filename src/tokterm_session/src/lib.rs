@@ -0,0 +1,5 @@
+extern crate tokterm_core;
+
+pub mod client;
+pub mod protocol;
+pub mod server;
@@ -0,0 +1,731 @@
+use std::io::{self, Read, Write};
+use tokterm_core::drawing::cell::Cell;
+use tokterm_core::drawing::color::Color;
+use tokterm_core::drawing::point_2d::Point2d;
+use tokterm_core::drawing::size_2d::Size2d;
+use tokterm_core::events::event::{
+    Event, KeyboardEvent, KeyboardEventType, MouseButton, MouseEvent, MouseEventType, WindowEvent,
+    WindowEventType,
+};
+use tokterm_core::input::key::Key;
+use tokterm_core::Error;
+use tokterm_core::Result;
+
+/// The largest message accepted, to avoid allocating for corrupted lengths.
+const MAX_MESSAGE_LENGTH: usize = 16 * 1024 * 1024;
+/// The largest width or height of a console, far beyond any real terminal
+/// while keeping its buffer allocatable.
+const MAX_SIZE: usize = 4096;
+
+/// The messages sent by an attached client.
+#[derive(Debug, Clone)]
+pub enum ClientMessage {
+    /// The client console size, sent on attach and whenever it changes.
+    Resize(Size2d),
+    /// An input event of the client.
+    Event(Event),
+    /// The client is detaching, the application keeps running.
+    Detach,
+}
+
+/// A run of consecutive cells that changed, starting at a cell index.
+#[derive(Debug, Clone)]
+pub struct CellRun {
+    pub index: usize,
+    pub cells: Vec<Cell>,
+}
+
+/// The messages sent by the session server.
+#[derive(Debug, Clone)]
+pub enum ServerMessage {
+    /// The cells that changed since the last frame. A frame with `full` set
+    /// replaces the whole screen.
+    Frame {
+        size: Size2d,
+        full: bool,
+        runs: Vec<CellRun>,
+    },
+    /// The cursor position and visibility.
+    Cursor { position: Point2d, visible: bool },
+    /// The application ended.
+    Close,
+}
+
+/// Builds the runs of cells that differ between two frames. All cells are
+/// included when there is no previous frame.
+pub fn diff_cells(previous: Option<&[Cell]>, cells: &[Cell]) -> Vec<CellRun> {
+    let mut runs: Vec<CellRun> = Vec::new();
+
+    for (index, cell) in cells.iter().enumerate() {
+        if let Some(previous) = previous {
+            if same_cell(&previous[index], cell) {
+                continue;
+            }
+        }
+
+        match runs.last_mut() {
+            Some(ref mut run) if run.index + run.cells.len() == index => run.cells.push(*cell),
+            _ => runs.push(CellRun {
+                index,
+                cells: vec![*cell],
+            }),
+        }
+    }
+
+    runs
+}
+
+/// Compares the visible parts of two cells.
+#[inline]
+pub fn same_cell(first: &Cell, second: &Cell) -> bool {
+    first.character == second.character
+        && first.foreground == second.foreground
+        && first.background == second.background
+}
+
+pub fn write_client_message<W: Write>(output: &mut W, message: &ClientMessage) -> Result<()> {
+    let mut payload = Vec::new();
+
+    match *message {
+        ClientMessage::Resize(size) => {
+            payload.push(0);
+            put_size(&mut payload, size);
+        }
+        ClientMessage::Event(ref event) => {
+            payload.push(1);
+            put_event(&mut payload, event);
+        }
+        ClientMessage::Detach => payload.push(2),
+    }
+
+    write_payload(output, &payload)
+}
+
+/// Reads the next client message, or `None` when the client disconnected.
+pub fn read_client_message<R: Read>(input: &mut R) -> Result<Option<ClientMessage>> {
+    let payload = match read_payload(input)? {
+        Some(payload) => payload,
+        None => return Ok(None),
+    };
+    let mut reader = Reader::new(&payload);
+
+    let message = match reader.u8()? {
+        0 => ClientMessage::Resize(reader.console_size()?),
+        1 => ClientMessage::Event(reader.event()?),
+        2 => ClientMessage::Detach,
+        _ => return Err(malformed()),
+    };
+
+    Ok(Some(message))
+}
+
+pub fn write_server_message<W: Write>(output: &mut W, message: &ServerMessage) -> Result<()> {
+    let mut payload = Vec::new();
+
+    match *message {
+        ServerMessage::Frame {
+            size,
+            full,
+            ref runs,
+        } => {
+            payload.push(0);
+            put_size(&mut payload, size);
+            payload.push(full as u8);
+            put_u32(&mut payload, runs.len() as u32);
+
+            for run in runs {
+                put_u32(&mut payload, run.index as u32);
+                put_u32(&mut payload, run.cells.len() as u32);
+
+                for cell in &run.cells {
+                    put_u32(&mut payload, cell.character as u32);
+                    payload.push(cell.foreground.to_u8());
+                    payload.push(cell.background.to_u8());
+                }
+            }
+        }
+        ServerMessage::Cursor { position, visible } => {
+            payload.push(1);
+            put_point(&mut payload, position);
+            payload.push(visible as u8);
+        }
+        ServerMessage::Close => payload.push(2),
+    }
+
+    write_payload(output, &payload)
+}
+
+/// Reads the next server message, or `None` when the server disconnected.
+pub fn read_server_message<R: Read>(input: &mut R) -> Result<Option<ServerMessage>> {
+    let payload = match read_payload(input)? {
+        Some(payload) => payload,
+        None => return Ok(None),
+    };
+    let mut reader = Reader::new(&payload);
+
+    let message = match reader.u8()? {
+        0 => {
+            let size = reader.size()?;
+            let full = reader.bool()?;
+            let run_count = reader.u32()? as usize;
+            let cell_count = size.width.checked_mul(size.height).ok_or_else(malformed)?;
+            let mut runs = Vec::new();
+
+            for _ in 0..run_count {
+                let index = reader.u32()? as usize;
+                let length = reader.u32()? as usize;
+                let mut cells = Vec::new();
+
+                // the runs have to fit in the frame.
+                match index.checked_add(length) {
+                    Some(end) if end <= cell_count => (),
+                    _ => return Err(malformed()),
+                }
+
+                for _ in 0..length {
+                    let character = reader.char()?;
+                    let foreground = reader.color()?;
+                    let background = reader.color()?;
+
+                    cells.push(Cell::new(character, foreground, background));
+                }
+
+                runs.push(CellRun { index, cells });
+            }
+
+            ServerMessage::Frame { size, full, runs }
+        }
+        1 => ServerMessage::Cursor {
+            position: reader.point()?,
+            visible: reader.bool()?,
+        },
+        2 => ServerMessage::Close,
+        _ => return Err(malformed()),
+    };
+
+    Ok(Some(message))
+}
+
+fn write_payload<W: Write>(output: &mut W, payload: &[u8]) -> Result<()> {
+    let mut message = Vec::with_capacity(payload.len() + 4);
+
+    put_u32(&mut message, payload.len() as u32);
+    message.extend_from_slice(payload);
+
+    if let Err(error) = output.write_all(&message).and_then(|_| output.flush()) {
+        return Err(Error::io("Couldn't send the session message.", error));
+    }
+
+    Ok(())
+}
+
+fn read_payload<R: Read>(input: &mut R) -> Result<Option<Vec<u8>>> {
+    let mut length = [0; 4];
+
+    match input.read_exact(&mut length) {
+        Ok(()) => (),
+        Err(ref error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(error) => return Err(Error::io("Couldn't receive the session message.", error)),
+    }
+
+    let length = u32::from_be_bytes(length) as usize;
+
+    if length > MAX_MESSAGE_LENGTH {
+        return Err(malformed());
+    }
+
+    let mut payload = vec![0; length];
+
+    if let Err(error) = input.read_exact(&mut payload) {
+        return Err(Error::io("Couldn't receive the session message.", error));
+    }
+
+    Ok(Some(payload))
+}
+
+fn put_u32(payload: &mut Vec<u8>, value: u32) {
+    payload.extend_from_slice(&value.to_be_bytes());
+}
+
+fn put_size(payload: &mut Vec<u8>, size: Size2d) {
+    put_u32(payload, size.width as u32);
+    put_u32(payload, size.height as u32);
+}
+
+fn put_point(payload: &mut Vec<u8>, point: Point2d) {
+    put_u32(payload, point.x as u32);
+    put_u32(payload, point.y as u32);
+}
+
+fn put_string(payload: &mut Vec<u8>, text: &str) {
+    put_u32(payload, text.len() as u32);
+    payload.extend_from_slice(text.as_bytes());
+}
+
+fn put_event(payload: &mut Vec<u8>, event: &Event) {
+    match *event {
+        Event::Mouse(mouse) => {
+            payload.push(0);
+            payload.push(mouse.event_type.to_u8());
+            payload.push(mouse.button.to_u8());
+            payload.push(
+                mouse.left_button as u8
+                    | (mouse.middle_button as u8) << 1
+                    | (mouse.right_button as u8) << 2
+                    | (mouse.extra_button_1 as u8) << 3
+                    | (mouse.extra_button_2 as u8) << 4
+                    | (mouse.extra_button_3 as u8) << 5
+                    | (mouse.extra_button_4 as u8) << 6,
+            );
+            put_point(payload, mouse.position);
+            payload.extend_from_slice(&mouse.wheel_delta.to_be_bytes());
+            payload.push(mouse.control as u8 | (mouse.shift as u8) << 1 | (mouse.menu as u8) << 2);
+        }
+        Event::Keyboard(keyboard) => {
+            payload.push(1);
+            payload.push(keyboard.event_type.to_u8());
+            put_u32(payload, keyboard.key.to_u32());
+            payload.extend_from_slice(&keyboard.key_code.to_be_bytes());
+            put_u32(payload, keyboard.character as u32);
+            payload.push(
+                keyboard.left_control as u8
+                    | (keyboard.left_shift as u8) << 1
+                    | (keyboard.left_menu as u8) << 2
+                    | (keyboard.right_control as u8) << 3
+                    | (keyboard.right_shift as u8) << 4
+                    | (keyboard.right_menu as u8) << 5,
+            );
+        }
+        Event::Window(window) => {
+            payload.push(2);
+            payload.push(window.event_type.to_u8());
+            put_point(payload, window.position);
+            put_size(payload, window.size);
+        }
+        Event::Clipboard(ref text) => {
+            payload.push(3);
+            put_string(payload, text);
+        }
+        Event::Paste(ref text) => {
+            payload.push(4);
+            put_string(payload, text);
+        }
+    }
+}
+
+/// Whether a size fits a console, neither empty nor larger than `MAX_SIZE`.
+pub fn is_console_size(size: Size2d) -> bool {
+    (1..=MAX_SIZE).contains(&size.width) && (1..=MAX_SIZE).contains(&size.height)
+}
+
+#[inline]
+fn malformed() -> Error {
+    Error::InvalidArgument("Malformed session message.")
+}
+
+/// Reads the values of a message payload in order.
+struct Reader<'a> {
+    payload: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(payload: &'a [u8]) -> Reader<'a> {
+        Reader { payload }
+    }
+
+    fn bytes(&mut self, count: usize) -> Result<&'a [u8]> {
+        if self.payload.len() < count {
+            return Err(malformed());
+        }
+
+        let (bytes, rest) = self.payload.split_at(count);
+        self.payload = rest;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn bool(&mut self) -> Result<bool> {
+        Ok(self.u8()? != 0)
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        let bytes = self.bytes(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let bytes = self.bytes(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn char(&mut self) -> Result<char> {
+        ::std::char::from_u32(self.u32()?).ok_or_else(malformed)
+    }
+
+    fn string(&mut self) -> Result<String> {
+        let length = self.u32()? as usize;
+
+        String::from_utf8(self.bytes(length)?.to_vec()).map_err(|_| malformed())
+    }
+
+    fn size(&mut self) -> Result<Size2d> {
+        Ok(Size2d::new(self.u32()? as usize, self.u32()? as usize))
+    }
+
+    /// Reads the size of a console, which can't be empty nor larger than
+    /// `MAX_SIZE`.
+    fn console_size(&mut self) -> Result<Size2d> {
+        let size = self.size()?;
+
+        if is_console_size(size) {
+            Ok(size)
+        } else {
+            Err(malformed())
+        }
+    }
+
+    fn point(&mut self) -> Result<Point2d> {
+        Ok(Point2d::new(self.u32()? as i32, self.u32()? as i32))
+    }
+
+    fn color(&mut self) -> Result<Color> {
+        self.value()
+    }
+
+    fn value<T: ByteValue>(&mut self) -> Result<T> {
+        T::from_u8(self.u8()?).ok_or_else(malformed)
+    }
+
+    fn event(&mut self) -> Result<Event> {
+        match self.u8()? {
+            0 => {
+                let event_type = self.value()?;
+                let button = self.value()?;
+                let buttons = self.u8()?;
+                let position = self.point()?;
+                let wheel_delta = self.u16()? as i16;
+                let modifiers = self.u8()?;
+
+                Ok(Event::Mouse(MouseEvent {
+                    event_type,
+                    button,
+                    left_button: buttons & 1 != 0,
+                    middle_button: buttons & 2 != 0,
+                    right_button: buttons & 4 != 0,
+                    extra_button_1: buttons & 8 != 0,
+                    extra_button_2: buttons & 16 != 0,
+                    extra_button_3: buttons & 32 != 0,
+                    extra_button_4: buttons & 64 != 0,
+                    position,
+                    wheel_delta,
+                    control: modifiers & 1 != 0,
+                    shift: modifiers & 2 != 0,
+                    menu: modifiers & 4 != 0,
+                }))
+            }
+            1 => {
+                let event_type = self.value()?;
+                let key = Key::from_u32(self.u32()?).ok_or_else(malformed)?;
+                let key_code = self.u16()?;
+                let character = self.char()?;
+                let modifiers = self.u8()?;
+
+                Ok(Event::Keyboard(KeyboardEvent {
+                    event_type,
+                    key,
+                    key_code,
+                    character,
+                    left_control: modifiers & 1 != 0,
+                    left_shift: modifiers & 2 != 0,
+                    left_menu: modifiers & 4 != 0,
+                    right_control: modifiers & 8 != 0,
+                    right_shift: modifiers & 16 != 0,
+                    right_menu: modifiers & 32 != 0,
+                }))
+            }
+            2 => Ok(Event::Window(WindowEvent {
+                event_type: self.value()?,
+                position: self.point()?,
+                size: self.size()?,
+            })),
+            3 => Ok(Event::Clipboard(self.string()?)),
+            4 => Ok(Event::Paste(self.string()?)),
+            _ => Err(malformed()),
+        }
+    }
+}
+
+/// The byte values of the enums sent in the messages, the encoding being kept
+/// next to the decoding.
+trait ByteValue: Sized {
+    fn to_u8(self) -> u8;
+    fn from_u8(value: u8) -> Option<Self>;
+}
+
+impl ByteValue for MouseEventType {
+    fn to_u8(self) -> u8 {
+        match self {
+            MouseEventType::MouseMove => 0,
+            MouseEventType::MouseDown => 1,
+            MouseEventType::MouseUp => 2,
+            MouseEventType::DoubleClick => 3,
+            MouseEventType::Wheel => 4,
+            MouseEventType::HorizontalWheel => 5,
+        }
+    }
+
+    fn from_u8(value: u8) -> Option<MouseEventType> {
+        match value {
+            0 => Some(MouseEventType::MouseMove),
+            1 => Some(MouseEventType::MouseDown),
+            2 => Some(MouseEventType::MouseUp),
+            3 => Some(MouseEventType::DoubleClick),
+            4 => Some(MouseEventType::Wheel),
+            5 => Some(MouseEventType::HorizontalWheel),
+            _ => None,
+        }
+    }
+}
+
+impl ByteValue for MouseButton {
+    fn to_u8(self) -> u8 {
+        match self {
+            MouseButton::None => 0,
+            MouseButton::Left => 1,
+            MouseButton::Middle => 2,
+            MouseButton::Right => 3,
+            MouseButton::Extra1 => 4,
+            MouseButton::Extra2 => 5,
+            MouseButton::Extra3 => 6,
+            MouseButton::Extra4 => 7,
+        }
+    }
+
+    fn from_u8(value: u8) -> Option<MouseButton> {
+        match value {
+            0 => Some(MouseButton::None),
+            1 => Some(MouseButton::Left),
+            2 => Some(MouseButton::Middle),
+            3 => Some(MouseButton::Right),
+            4 => Some(MouseButton::Extra1),
+            5 => Some(MouseButton::Extra2),
+            6 => Some(MouseButton::Extra3),
+            7 => Some(MouseButton::Extra4),
+            _ => None,
+        }
+    }
+}
+
+impl ByteValue for KeyboardEventType {
+    fn to_u8(self) -> u8 {
+        match self {
+            KeyboardEventType::KeyDown => 0,
+            KeyboardEventType::KeyUp => 1,
+        }
+    }
+
+    fn from_u8(value: u8) -> Option<KeyboardEventType> {
+        match value {
+            0 => Some(KeyboardEventType::KeyDown),
+            1 => Some(KeyboardEventType::KeyUp),
+            _ => None,
+        }
+    }
+}
+
+impl ByteValue for WindowEventType {
+    fn to_u8(self) -> u8 {
+        match self {
+            WindowEventType::WindowMove => 0,
+            WindowEventType::WindowResize => 1,
+            WindowEventType::WindowFocus => 2,
+            WindowEventType::WindowLostFocus => 3,
+            WindowEventType::WindowClose => 4,
+            WindowEventType::WindowResume => 5,
+        }
+    }
+
+    fn from_u8(value: u8) -> Option<WindowEventType> {
+        match value {
+            0 => Some(WindowEventType::WindowMove),
+            1 => Some(WindowEventType::WindowResize),
+            2 => Some(WindowEventType::WindowFocus),
+            3 => Some(WindowEventType::WindowLostFocus),
+            4 => Some(WindowEventType::WindowClose),
+            5 => Some(WindowEventType::WindowResume),
+            _ => None,
+        }
+    }
+}
+
+impl ByteValue for Color {
+    fn to_u8(self) -> u8 {
+        match self {
+            Color::Black => 0,
+            Color::Red => 1,
+            Color::Green => 2,
+            Color::Yellow => 3,
+            Color::Blue => 4,
+            Color::Magenta => 5,
+            Color::Cyan => 6,
+            Color::White => 7,
+            Color::DarkGrey => 8,
+            Color::DarkRed => 9,
+            Color::DarkGreen => 10,
+            Color::DarkYellow => 11,
+            Color::DarkBlue => 12,
+            Color::DarkMagenta => 13,
+            Color::DarkCyan => 14,
+            Color::Grey => 15,
+        }
+    }
+
+    fn from_u8(value: u8) -> Option<Color> {
+        match value {
+            0 => Some(Color::Black),
+            1 => Some(Color::Red),
+            2 => Some(Color::Green),
+            3 => Some(Color::Yellow),
+            4 => Some(Color::Blue),
+            5 => Some(Color::Magenta),
+            6 => Some(Color::Cyan),
+            7 => Some(Color::White),
+            8 => Some(Color::DarkGrey),
+            9 => Some(Color::DarkRed),
+            10 => Some(Color::DarkGreen),
+            11 => Some(Color::DarkYellow),
+            12 => Some(Color::DarkBlue),
+            13 => Some(Color::DarkMagenta),
+            14 => Some(Color::DarkCyan),
+            15 => Some(Color::Grey),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_frame(size: Size2d, index: u32, length: u32) -> Result<Option<ServerMessage>> {
+        let mut payload = vec![0];
+        put_size(&mut payload, size);
+        payload.push(0);
+        put_u32(&mut payload, 1);
+        put_u32(&mut payload, index);
+        put_u32(&mut payload, length);
+
+        for _ in 0..length {
+            put_u32(&mut payload, 'x' as u32);
+            payload.push(Color::Grey.to_u8());
+            payload.push(Color::Black.to_u8());
+        }
+
+        let mut message = Vec::new();
+        write_payload(&mut message, &payload).unwrap();
+        read_server_message(&mut &message[..])
+    }
+
+    #[test]
+    fn events_are_read_as_written() {
+        let events = vec![
+            Event::Mouse(MouseEvent {
+                event_type: MouseEventType::HorizontalWheel,
+                button: MouseButton::Extra4,
+                left_button: true,
+                middle_button: false,
+                right_button: false,
+                extra_button_1: false,
+                extra_button_2: false,
+                extra_button_3: false,
+                extra_button_4: true,
+                position: Point2d::new(3, 4),
+                wheel_delta: -120,
+                control: false,
+                shift: true,
+                menu: false,
+            }),
+            Event::Keyboard(KeyboardEvent {
+                event_type: KeyboardEventType::KeyUp,
+                key: Key::A,
+                key_code: 'a' as u16,
+                character: 'a',
+                left_control: false,
+                left_shift: false,
+                left_menu: true,
+                right_control: false,
+                right_shift: false,
+                right_menu: false,
+            }),
+            Event::Window(WindowEvent {
+                event_type: WindowEventType::WindowResume,
+                position: Point2d::empty(),
+                size: Size2d::new(80, 24),
+            }),
+            Event::Paste(String::from("text")),
+        ];
+
+        for event in events {
+            let mut message = Vec::new();
+            write_client_message(&mut message, &ClientMessage::Event(event.clone())).unwrap();
+
+            match read_client_message(&mut &message[..]).unwrap() {
+                Some(ClientMessage::Event(read)) => assert_eq!(read, event),
+                message => panic!("unexpected message {:?}", message),
+            }
+        }
+    }
+
+    #[test]
+    fn colors_are_read_as_written() {
+        for color in Color::to_vec() {
+            assert_eq!(Color::from_u8(color.to_u8()), Some(color));
+        }
+
+        assert_eq!(Color::from_u8(16), None);
+    }
+
+    #[test]
+    fn frames_fitting_the_size_are_accepted() {
+        assert!(read_frame(Size2d::new(4, 2), 5, 3).is_ok());
+    }
+
+    #[test]
+    fn frames_with_runs_past_the_size_are_rejected() {
+        assert!(read_frame(Size2d::new(4, 2), 6, 3).is_err());
+        assert!(read_frame(Size2d::new(4, 2), u32::MAX, 1).is_err());
+    }
+
+    #[test]
+    fn resizes_to_console_sizes_are_accepted() {
+        let mut message = Vec::new();
+        write_client_message(&mut message, &ClientMessage::Resize(Size2d::new(4096, 1))).unwrap();
+
+        match read_client_message(&mut &message[..]).unwrap() {
+            Some(ClientMessage::Resize(size)) => assert_eq!(size, Size2d::new(4096, 1)),
+            message => panic!("unexpected message {:?}", message),
+        }
+    }
+
+    #[test]
+    fn resizes_to_empty_or_huge_sizes_are_rejected() {
+        let sizes = [
+            Size2d::empty(),
+            Size2d::new(0, 24),
+            Size2d::new(80, 0),
+            Size2d::new(4097, 24),
+            Size2d::new(u32::MAX as usize, u32::MAX as usize),
+        ];
+
+        for size in &sizes {
+            let mut message = Vec::new();
+            write_client_message(&mut message, &ClientMessage::Resize(*size)).unwrap();
+
+            assert!(!is_console_size(*size));
+            assert!(read_client_message(&mut &message[..]).is_err());
+        }
+    }
+}
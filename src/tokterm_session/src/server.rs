@@ -0,0 +1,392 @@
+use protocol::{
+    diff_cells, read_client_message, write_server_message, ClientMessage, ServerMessage,
+};
+use std::fs;
+use std::io;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::thread;
+use std::time::Duration;
+use tokterm_core::drawing::cell::Cell;
+use tokterm_core::drawing::cell_buffer::CellBuffer;
+use tokterm_core::drawing::palette::Palette;
+use tokterm_core::drawing::point_2d::Point2d;
+use tokterm_core::drawing::size_2d::Size2d;
+use tokterm_core::events::event::{Event, WindowEvent, WindowEventType};
+use tokterm_core::events::event_queue::EventQueue;
+use tokterm_core::input::keyboard_state::KeyboardState;
+use tokterm_core::input::mouse_state::MouseState;
+use tokterm_core::system::application::Application;
+use tokterm_core::system::capabilities::{Capabilities, MouseSupport};
+use tokterm_core::system::terminal::Terminal;
+use tokterm_core::Error;
+use tokterm_core::Result;
+
+/// The console size used until a client attaches.
+const DEFAULT_SIZE: Size2d = Size2d {
+    width: 80,
+    height: 24,
+};
+
+/// How long a stalled client can block a frame before it is detached.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// A terminal rendering into memory and forwarding the changes of every
+/// frame to the attached client, if any.
+pub struct SessionTerminal {
+    size: Size2d,
+    client: Option<UnixStream>,
+    last_frame: Option<CellBuffer>,
+    sent_frame: Option<CellBuffer>,
+    cursor: Point2d,
+    cursor_visible: bool,
+    palette: Option<Palette>,
+}
+
+#[allow(dead_code)]
+impl SessionTerminal {
+    pub fn new(size: Size2d) -> SessionTerminal {
+        SessionTerminal {
+            size,
+            client: None,
+            last_frame: None,
+            sent_frame: None,
+            cursor: Point2d::empty(),
+            cursor_visible: true,
+            palette: None,
+        }
+    }
+
+    #[inline]
+    pub fn is_attached(&self) -> bool {
+        self.client.is_some()
+    }
+
+    /// Gets the last frame drawn by the application.
+    #[inline]
+    pub fn get_last_frame(&self) -> Option<&CellBuffer> {
+        self.last_frame.as_ref()
+    }
+
+    #[inline]
+    pub fn get_palette(&self) -> Option<&Palette> {
+        self.palette.as_ref()
+    }
+
+    #[inline]
+    pub fn set_console_size(&mut self, size: Size2d) {
+        self.size = size;
+    }
+
+    /// Attaches a client, replacing the current one, and sends it the last
+    /// frame so it doesn't wait for the next one.
+    pub fn attach(&mut self, client: UnixStream) {
+        self.client = Some(client);
+        self.sent_frame = None;
+
+        if let Some(frame) = self.last_frame.take() {
+            self.send_frame(&frame);
+            self.last_frame = Some(frame);
+        }
+
+        self.send_cursor();
+    }
+
+    pub fn detach(&mut self) {
+        self.client = None;
+        self.sent_frame = None;
+    }
+
+    fn send_frame(&mut self, frame: &CellBuffer) {
+        let cells: Vec<Cell> = frame.iter().cloned().collect();
+        let previous: Option<Vec<Cell>> = match self.sent_frame {
+            Some(ref sent) if sent.size == frame.size => Some(sent.iter().cloned().collect()),
+            _ => None,
+        };
+        let runs = diff_cells(previous.as_ref().map(|cells| &cells[..]), &cells);
+
+        if previous.is_some() && runs.is_empty() {
+            return;
+        }
+
+        self.send(&ServerMessage::Frame {
+            size: frame.size,
+            full: previous.is_none(),
+            runs,
+        });
+        self.sent_frame = Some(frame.clone());
+    }
+
+    fn send_cursor(&mut self) {
+        let message = ServerMessage::Cursor {
+            position: self.cursor,
+            visible: self.cursor_visible,
+        };
+
+        self.send(&message);
+    }
+
+    /// Sends a message to the client, detaching it when it can't be reached
+    /// so the application keeps running.
+    fn send(&mut self, message: &ServerMessage) {
+        let failed = match self.client {
+            Some(ref mut client) => write_server_message(client, message).is_err(),
+            None => false,
+        };
+
+        if failed {
+            self.detach();
+        }
+    }
+}
+
+impl Terminal for SessionTerminal {
+    /// Shows or hides the cursor.
+    fn set_cursor_visibility(&mut self, visible: bool) -> Result<()> {
+        self.cursor_visible = visible;
+        self.send_cursor();
+        Ok(())
+    }
+
+    /// Moves the console cursor to a given position.
+    fn set_cursor(&mut self, position: Point2d) -> Result<()> {
+        self.cursor = position;
+        self.send_cursor();
+        Ok(())
+    }
+
+    /// Gets the current console size in character units.
+    fn get_console_size(&self) -> Result<Size2d> {
+        Ok(self.size)
+    }
+
+    /// Clears the console screen.
+    fn clear(&mut self) -> Result<()> {
+        let blank = CellBuffer::new(Cell::default(' '), self.size);
+
+        self.send_frame(&blank);
+        self.last_frame = Some(blank);
+        Ok(())
+    }
+
+    /// Draws a `CellBuffer` to the screen.
+    fn write(&mut self, cell_buffer: &mut CellBuffer) -> Result<()> {
+        self.send_frame(cell_buffer);
+        self.last_frame = Some(cell_buffer.clone());
+        Ok(())
+    }
+
+    /// Sets the palette used to present the base colors, or restores the
+    /// terminal's own colors when `None` is given. Clients always present
+    /// the base colors with their own palette.
+    fn set_palette(&mut self, palette: Option<&Palette>) -> Result<()> {
+        self.palette = palette.cloned();
+        Ok(())
+    }
+
+    /// Gets what the terminal supports, detected from the environment unless
+    /// the backend knows better.
+    fn capabilities(&self) -> Capabilities {
+        // clients present the base colors and forward their mouse events,
+        // whatever the terminal of the server is.
+        Capabilities {
+            mouse: MouseSupport::Sgr,
+            ..Capabilities::basic()
+        }
+    }
+}
+
+/// An application that keeps running in the background while clients
+/// attach and detach through a Unix domain socket.
+pub struct SessionApplication {
+    terminal: SessionTerminal,
+    listener: UnixListener,
+    path: PathBuf,
+    input: Option<Receiver<Result<Option<ClientMessage>>>>,
+    event_queue: EventQueue,
+    mouse_state: MouseState,
+    keyboard_state: KeyboardState,
+}
+
+impl SessionApplication {
+    /// Listens for clients on a socket path. A stale socket left by a
+    /// crashed session is replaced, a live one is an error.
+    pub fn bind<P: AsRef<Path>>(path: P) -> Result<SessionApplication> {
+        let path = path.as_ref().to_path_buf();
+
+        if path.exists() {
+            if UnixStream::connect(&path).is_ok() {
+                return Err(Error::InvalidOperation(
+                    "A session is already running on the socket.",
+                ));
+            }
+
+            if let Err(error) = fs::remove_file(&path) {
+                return Err(Error::io("Couldn't remove the stale socket.", error));
+            }
+        }
+
+        let listener = match UnixListener::bind(&path) {
+            Ok(listener) => listener,
+            Err(error) => return Err(Error::io("Couldn't listen on the socket.", error)),
+        };
+
+        if let Err(error) = listener.set_nonblocking(true) {
+            return Err(Error::io("Couldn't configure the socket.", error));
+        }
+
+        Ok(SessionApplication {
+            terminal: SessionTerminal::new(DEFAULT_SIZE),
+            listener,
+            path,
+            input: None,
+            event_queue: EventQueue::new(),
+            mouse_state: MouseState::new(),
+            keyboard_state: KeyboardState::new(),
+        })
+    }
+
+    #[inline]
+    pub fn get_session_terminal(&self) -> &SessionTerminal {
+        &self.terminal
+    }
+
+    #[inline]
+    pub fn get_mut_session_terminal(&mut self) -> &mut SessionTerminal {
+        &mut self.terminal
+    }
+
+    #[inline]
+    pub fn is_attached(&self) -> bool {
+        self.terminal.is_attached()
+    }
+
+    /// Attaches the clients waiting on the socket, the last one wins.
+    fn accept_clients(&mut self) -> Result<()> {
+        loop {
+            let stream = match self.listener.accept() {
+                Ok((stream, _)) => stream,
+                Err(ref error) if error.kind() == io::ErrorKind::WouldBlock => return Ok(()),
+                Err(error) => return Err(Error::io("Couldn't accept the client.", error)),
+            };
+
+            let reader = stream
+                .set_nonblocking(false)
+                .and_then(|_| stream.set_write_timeout(Some(WRITE_TIMEOUT)))
+                .and_then(|_| stream.try_clone());
+
+            // a client failing to set up is dropped, like a detached one.
+            if let Ok(reader) = reader {
+                self.input = Some(spawn_reader(reader));
+                self.terminal.attach(stream);
+            }
+        }
+    }
+
+    fn process_message(&mut self, message: ClientMessage) {
+        match message {
+            ClientMessage::Resize(size) => {
+                if size != self.terminal.size {
+                    self.terminal.set_console_size(size);
+                    self.event_queue.add_event(Event::Window(WindowEvent {
+                        event_type: WindowEventType::WindowResize,
+                        position: Point2d::empty(),
+                        size,
+                    }));
+                }
+            }
+            ClientMessage::Event(event) => {
+                match event {
+                    Event::Mouse(mouse) => self.mouse_state.update_from_event(mouse),
+                    Event::Keyboard(keyboard) => self.keyboard_state.update_from_event(keyboard),
+                    _ => (),
+                }
+
+                self.event_queue.add_event(event);
+            }
+            ClientMessage::Detach => self.detach(),
+        }
+    }
+
+    fn detach(&mut self) {
+        self.input = None;
+        self.terminal.detach();
+    }
+}
+
+impl Drop for SessionApplication {
+    fn drop(&mut self) {
+        self.terminal.send(&ServerMessage::Close);
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+impl Application for SessionApplication {
+    #[inline]
+    fn get_terminal(&self) -> &Terminal {
+        &self.terminal
+    }
+
+    #[inline]
+    fn get_mut_terminal(&mut self) -> &mut Terminal {
+        &mut self.terminal
+    }
+
+    #[inline]
+    fn get_mouse_state(&self) -> &MouseState {
+        &self.mouse_state
+    }
+
+    #[inline]
+    fn get_keyboard_state(&self) -> &KeyboardState {
+        &self.keyboard_state
+    }
+
+    #[inline]
+    fn get_event_queue(&self) -> &EventQueue {
+        &self.event_queue
+    }
+
+    #[inline]
+    fn get_mut_event_queue(&mut self) -> &mut EventQueue {
+        &mut self.event_queue
+    }
+
+    fn listen_events(&mut self) -> Result<()> {
+        self.accept_clients()?;
+
+        loop {
+            let message = match self.input {
+                Some(ref input) => match input.try_recv() {
+                    Ok(Ok(Some(message))) => message,
+                    Err(TryRecvError::Empty) => return Ok(()),
+                    // a closed or misbehaving client is just detached.
+                    Ok(Ok(None)) | Ok(Err(_)) | Err(TryRecvError::Disconnected) => {
+                        ClientMessage::Detach
+                    }
+                },
+                None => return Ok(()),
+            };
+
+            self.process_message(message);
+        }
+    }
+}
+
+/// Reads the client messages on their own thread, until the client
+/// disconnects or sends a malformed message.
+fn spawn_reader(mut stream: UnixStream) -> Receiver<Result<Option<ClientMessage>>> {
+    let (sender, receiver) = channel();
+
+    thread::spawn(move || loop {
+        let message = read_client_message(&mut stream);
+        let done = !matches!(message, Ok(Some(_)));
+
+        if sender.send(message).is_err() || done {
+            return;
+        }
+    });
+
+    receiver
+}
@@ -0,0 +1,244 @@
+use protocol::{TelnetDecoder, NEGOTIATION};
+use std::io::{self, Write};
+use std::net::{Shutdown, TcpStream};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, TryRecvError};
+use std::time::{Duration, Instant};
+use tokterm_ansi::application::spawn_reader;
+use tokterm_ansi::terminal::AnsiTerminal;
+use tokterm_core::drawing::point_2d::Point2d;
+use tokterm_core::drawing::size_2d::Size2d;
+use tokterm_core::events::event::{Event, WindowEvent, WindowEventType};
+use tokterm_core::events::event_queue::EventQueue;
+use tokterm_core::input::keyboard_state::KeyboardState;
+use tokterm_core::input::kitty::{ENABLE_KITTY_KEYBOARD, KITTY_KEYBOARD_QUERY};
+use tokterm_core::input::mouse_state::MouseState;
+use tokterm_core::input::parser::InputParser;
+use tokterm_core::input::sgr_mouse::ENABLE_SGR_MOUSE;
+use tokterm_core::system::application::Application;
+use tokterm_core::system::capabilities::MouseSupport;
+use tokterm_core::system::focus::ENABLE_FOCUS_EVENTS;
+use tokterm_core::system::paste::ENABLE_BRACKETED_PASTE;
+use tokterm_core::system::terminal::Terminal;
+use tokterm_core::Error;
+use tokterm_core::Result;
+
+/// The console size used until the client reports its own.
+const DEFAULT_SIZE: Size2d = Size2d {
+    width: 80,
+    height: 24,
+};
+
+/// How long a new connection waits for the client to report its size.
+const NAWS_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// The most bytes of an incomplete sequence kept from a client, so a paste or
+/// a reply that never ends can't grow without bounds.
+const MAX_PENDING_INPUT: usize = 64 * 1024;
+
+/// An application served to a telnet client over a TCP connection.
+pub struct TelnetApplication {
+    terminal: AnsiTerminal<TcpStream>,
+    input: Receiver<io::Result<Vec<u8>>>,
+    decoder: TelnetDecoder,
+    parser: InputParser,
+    event_queue: EventQueue,
+    mouse_state: MouseState,
+    keyboard_state: KeyboardState,
+}
+
+impl TelnetApplication {
+    /// Negotiates character mode and the window size with a connected client.
+    pub fn new(stream: TcpStream) -> Result<TelnetApplication> {
+        let mut output = match stream.try_clone() {
+            Ok(output) => output,
+            Err(error) => return Err(Error::io("Couldn't clone the connection.", error)),
+        };
+
+        if let Err(error) = stream.set_nodelay(true) {
+            return Err(Error::io("Couldn't configure the connection.", error));
+        }
+
+        if let Err(error) = output.write_all(&NEGOTIATION) {
+            return Err(Error::io("Couldn't negotiate with the client.", error));
+        }
+
+        let mut terminal = AnsiTerminal::new(output, DEFAULT_SIZE);
+
+        if terminal.capabilities().mouse == MouseSupport::Sgr {
+            terminal.write_sequence(ENABLE_SGR_MOUSE)?;
+        }
+
+        if terminal.capabilities().focus_events {
+            terminal.write_sequence(ENABLE_FOCUS_EVENTS)?;
+        }
+
+        if terminal.capabilities().bracketed_paste {
+            terminal.write_sequence(ENABLE_BRACKETED_PASTE)?;
+        }
+
+        if terminal.capabilities().kitty_keyboard {
+            terminal.write_sequence(ENABLE_KITTY_KEYBOARD)?;
+            terminal.write_sequence(KITTY_KEYBOARD_QUERY)?;
+        }
+
+        let mut application = TelnetApplication {
+            terminal,
+            input: spawn_reader(stream),
+            decoder: TelnetDecoder::new(),
+            parser: InputParser::with_limit(MAX_PENDING_INPUT),
+            event_queue: EventQueue::new(),
+            mouse_state: MouseState::new(),
+            keyboard_state: KeyboardState::new(),
+        };
+
+        application.wait_for_size()?;
+        Ok(application)
+    }
+
+    #[inline]
+    pub fn get_ansi_terminal(&mut self) -> &mut AnsiTerminal<TcpStream> {
+        &mut self.terminal
+    }
+
+    /// Gets the address of the connected client.
+    pub fn get_peer_address(&mut self) -> Result<::std::net::SocketAddr> {
+        match self.terminal.get_output().peer_addr() {
+            Ok(address) => Ok(address),
+            Err(error) => Err(Error::io("Couldn't get the client address.", error)),
+        }
+    }
+
+    /// Closes the connection with the client.
+    pub fn disconnect(&mut self) -> Result<()> {
+        match self.terminal.get_output().shutdown(Shutdown::Both) {
+            Ok(()) => Ok(()),
+            Err(error) => Err(Error::io("Couldn't close the connection.", error)),
+        }
+    }
+
+    /// Waits a moment for the first window size, so the application starts
+    /// drawing with the right size. Clients without NAWS keep the default.
+    fn wait_for_size(&mut self) -> Result<()> {
+        let deadline = Instant::now() + NAWS_TIMEOUT;
+
+        loop {
+            let now = Instant::now();
+
+            if now >= deadline {
+                break;
+            }
+
+            match self.input.recv_timeout(deadline - now) {
+                Ok(read) => {
+                    if self.process_input(read)? {
+                        break;
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return Err(Error::TerminalClosed),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Processes a read from the client, returning whether it reported its
+    /// window size.
+    fn process_input(&mut self, read: io::Result<Vec<u8>>) -> Result<bool> {
+        let bytes = match read {
+            Ok(bytes) => bytes,
+            Err(error) => return Err(Error::io("Couldn't read the input.", error)),
+        };
+
+        if bytes.is_empty() {
+            return Err(Error::TerminalClosed);
+        }
+
+        let mut data = Vec::with_capacity(bytes.len());
+        let mut replies = Vec::new();
+        let size = self.decoder.decode(&bytes, &mut data, &mut replies);
+
+        if !replies.is_empty() {
+            if let Err(error) = self.terminal.get_output().write_all(&replies) {
+                return Err(Error::io("Couldn't negotiate with the client.", error));
+            }
+        }
+
+        if let Some(size) = size {
+            self.terminal.set_console_size(size);
+            self.event_queue.add_event(Event::Window(WindowEvent {
+                event_type: WindowEventType::WindowResize,
+                position: Point2d::empty(),
+                size,
+            }));
+        }
+
+        let events = self.parser.parse(&data);
+        self.process_events(events)?;
+        Ok(size.is_some())
+    }
+
+    fn process_events(&mut self, events: Vec<Event>) -> Result<()> {
+        for mut event in events {
+            match event {
+                Event::Mouse(mouse) => self.mouse_state.update_from_event(mouse),
+                Event::Keyboard(keyboard) => self.keyboard_state.update_from_event(keyboard),
+                // the focus reports don't tell the size, unlike native events.
+                Event::Window(ref mut window) => window.size = self.terminal.get_console_size()?,
+                _ => (),
+            }
+
+            self.event_queue.add_event(event);
+        }
+
+        Ok(())
+    }
+}
+
+impl Application for TelnetApplication {
+    #[inline]
+    fn get_terminal(&self) -> &Terminal {
+        &self.terminal
+    }
+
+    #[inline]
+    fn get_mut_terminal(&mut self) -> &mut Terminal {
+        &mut self.terminal
+    }
+
+    #[inline]
+    fn get_mouse_state(&self) -> &MouseState {
+        &self.mouse_state
+    }
+
+    #[inline]
+    fn get_keyboard_state(&self) -> &KeyboardState {
+        &self.keyboard_state
+    }
+
+    #[inline]
+    fn get_event_queue(&self) -> &EventQueue {
+        &self.event_queue
+    }
+
+    #[inline]
+    fn get_mut_event_queue(&mut self) -> &mut EventQueue {
+        &mut self.event_queue
+    }
+
+    fn listen_events(&mut self) -> Result<()> {
+        loop {
+            match self.input.try_recv() {
+                Ok(read) => {
+                    self.process_input(read)?;
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => return Err(Error::TerminalClosed),
+            }
+        }
+
+        // an escape left alone for a while was the escape key.
+        let events = self.parser.check_timeout();
+        self.process_events(events)
+    }
+}
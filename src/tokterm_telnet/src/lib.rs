@@ -0,0 +1,6 @@
+extern crate tokterm_ansi;
+extern crate tokterm_core;
+
+pub mod application;
+pub mod protocol;
+pub mod server;
@@ -0,0 +1,256 @@
+use tokterm_core::drawing::size_2d::Size2d;
+
+pub const IAC: u8 = 255;
+pub const DONT: u8 = 254;
+pub const DO: u8 = 253;
+pub const WONT: u8 = 252;
+pub const WILL: u8 = 251;
+pub const SB: u8 = 250;
+pub const SE: u8 = 240;
+
+pub const ECHO: u8 = 1;
+pub const SUPPRESS_GO_AHEAD: u8 = 3;
+pub const NAWS: u8 = 31;
+pub const LINEMODE: u8 = 34;
+
+/// The options negotiated on connection: the server echoes (so the client
+/// doesn't), go-aheads are suppressed in both ways, which together put the
+/// client in character mode, and the client is asked for its window size.
+pub const NEGOTIATION: [u8; 15] = [
+    IAC,
+    WILL,
+    ECHO,
+    IAC,
+    WILL,
+    SUPPRESS_GO_AHEAD,
+    IAC,
+    DO,
+    SUPPRESS_GO_AHEAD,
+    IAC,
+    DO,
+    NAWS,
+    IAC,
+    DONT,
+    LINEMODE,
+];
+
+/// The longest subnegotiation kept, the NAWS one: the option and two 16 bits
+/// numbers. Longer ones are dropped.
+const MAX_SUBNEGOTIATION: usize = 5;
+
+/// The states of the telnet stream decoder.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum State {
+    Data,
+    CarriageReturn,
+    Command,
+    Option(u8),
+    Subnegotiation,
+    SubnegotiationCommand,
+}
+
+/// Separates the telnet commands from the terminal input, keeping the
+/// incomplete commands until the rest of their bytes arrive.
+pub struct TelnetDecoder {
+    state: State,
+    subnegotiation: Vec<u8>,
+}
+
+#[allow(dead_code)]
+impl TelnetDecoder {
+    pub fn new() -> TelnetDecoder {
+        TelnetDecoder {
+            state: State::Data,
+            subnegotiation: Vec::new(),
+        }
+    }
+
+    /// Decodes a chunk of the stream, appending the terminal input to `data`
+    /// and the answers to the client's negotiation to `replies`. Returns the
+    /// window size when the client reported a new one.
+    pub fn decode(
+        &mut self,
+        bytes: &[u8],
+        data: &mut Vec<u8>,
+        replies: &mut Vec<u8>,
+    ) -> Option<Size2d> {
+        let mut size = None;
+
+        for &byte in bytes {
+            self.state = match self.state {
+                State::Data | State::CarriageReturn if byte == IAC => State::Command,
+                // a carriage return is sent as CR NUL or CR LF, both are the
+                // return key.
+                State::CarriageReturn if byte == 0 || byte == b'\n' => State::Data,
+                State::Data | State::CarriageReturn => {
+                    data.push(byte);
+
+                    if byte == b'\r' {
+                        State::CarriageReturn
+                    } else {
+                        State::Data
+                    }
+                }
+                State::Command => match byte {
+                    IAC => {
+                        data.push(IAC);
+                        State::Data
+                    }
+                    WILL | WONT | DO | DONT => State::Option(byte),
+                    SB => {
+                        self.subnegotiation.clear();
+                        State::Subnegotiation
+                    }
+                    _ => State::Data,
+                },
+                State::Option(command) => {
+                    push_option_reply(command, byte, replies);
+                    State::Data
+                }
+                State::Subnegotiation => match byte {
+                    IAC => State::SubnegotiationCommand,
+                    _ => {
+                        self.push_subnegotiation(byte);
+                        State::Subnegotiation
+                    }
+                },
+                State::SubnegotiationCommand => match byte {
+                    SE => {
+                        if let Some(new_size) = parse_naws(&self.subnegotiation) {
+                            size = Some(new_size);
+                        }
+
+                        State::Data
+                    }
+                    _ => {
+                        self.push_subnegotiation(byte);
+                        State::Subnegotiation
+                    }
+                },
+            };
+        }
+
+        size
+    }
+
+    /// Keeps a byte of the subnegotiation. It stops growing one byte past
+    /// `MAX_SUBNEGOTIATION`, which is enough for `parse_naws` to drop it.
+    fn push_subnegotiation(&mut self, byte: u8) {
+        if self.subnegotiation.len() <= MAX_SUBNEGOTIATION {
+            self.subnegotiation.push(byte);
+        }
+    }
+}
+
+impl Default for TelnetDecoder {
+    fn default() -> TelnetDecoder {
+        TelnetDecoder::new()
+    }
+}
+
+/// Refuses the options the client offers or asks for that the server doesn't
+/// support. The replies to the server's own negotiation are accepted as they
+/// come, and so are the refusals, which need no answer.
+fn push_option_reply(command: u8, option: u8, replies: &mut Vec<u8>) {
+    let reply = match command {
+        WILL if option != SUPPRESS_GO_AHEAD && option != NAWS => DONT,
+        DO if option != ECHO && option != SUPPRESS_GO_AHEAD => WONT,
+        _ => return,
+    };
+
+    replies.extend_from_slice(&[IAC, reply, option]);
+}
+
+/// Parses a NAWS subnegotiation, made of the option and two 16 bits big
+/// endian numbers for the width and the height.
+fn parse_naws(subnegotiation: &[u8]) -> Option<Size2d> {
+    match subnegotiation {
+        [NAWS, width_high, width_low, height_high, height_low] => {
+            let width = (*width_high as usize) << 8 | *width_low as usize;
+            let height = (*height_high as usize) << 8 | *height_low as usize;
+
+            if width == 0 || height == 0 {
+                None
+            } else {
+                Some(Size2d::new(width, height))
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TERMINAL_TYPE: u8 = 24;
+
+    fn decode(bytes: &[u8]) -> (Vec<u8>, Vec<u8>, Option<Size2d>) {
+        let mut decoder = TelnetDecoder::new();
+        let mut data = Vec::new();
+        let mut replies = Vec::new();
+        let size = decoder.decode(bytes, &mut data, &mut replies);
+
+        (data, replies, size)
+    }
+
+    #[test]
+    fn naws_reports_the_size() {
+        let (data, _, size) = decode(&[b'a', IAC, SB, NAWS, 0, 80, 0, 24, IAC, SE, b'b']);
+
+        assert_eq!(data, b"ab");
+        assert_eq!(size, Some(Size2d::new(80, 24)));
+    }
+
+    #[test]
+    fn naws_keeps_escaped_bytes() {
+        let (_, _, size) = decode(&[IAC, SB, NAWS, 1, IAC, IAC, 0, 50, IAC, SE]);
+
+        assert_eq!(size, Some(Size2d::new(511, 50)));
+    }
+
+    #[test]
+    fn oversized_subnegotiations_are_dropped() {
+        let mut bytes = vec![IAC, SB, NAWS, 0, 80, 0, 24];
+        bytes.extend_from_slice(&[0; 1000]);
+        bytes.extend_from_slice(&[IAC, SE, b'a']);
+
+        let mut decoder = TelnetDecoder::new();
+        let mut data = Vec::new();
+        let mut replies = Vec::new();
+
+        assert_eq!(decoder.decode(&bytes, &mut data, &mut replies), None);
+        assert!(decoder.subnegotiation.len() <= MAX_SUBNEGOTIATION + 1);
+        assert_eq!(data, b"a");
+    }
+
+    #[test]
+    fn unsupported_options_are_refused() {
+        let (_, replies, _) = decode(&[IAC, WILL, TERMINAL_TYPE, IAC, DO, LINEMODE]);
+
+        assert_eq!(replies, [IAC, DONT, TERMINAL_TYPE, IAC, WONT, LINEMODE]);
+    }
+
+    #[test]
+    fn negotiation_replies_are_accepted() {
+        let (_, replies, _) = decode(&[
+            IAC,
+            DO,
+            ECHO,
+            IAC,
+            DO,
+            SUPPRESS_GO_AHEAD,
+            IAC,
+            WILL,
+            SUPPRESS_GO_AHEAD,
+            IAC,
+            WILL,
+            NAWS,
+            IAC,
+            WONT,
+            LINEMODE,
+        ]);
+
+        assert!(replies.is_empty());
+    }
+}
@@ -0,0 +1,61 @@
+use application::TelnetApplication;
+use std::net::{SocketAddr, TcpListener, ToSocketAddrs};
+use std::sync::Arc;
+use std::thread;
+use tokterm_core::Error;
+use tokterm_core::Result;
+
+/// Listens for telnet clients, giving each connection its own
+/// `TelnetApplication`.
+pub struct TelnetServer {
+    listener: TcpListener,
+}
+
+impl TelnetServer {
+    /// Listens on the given address, like `127.0.0.1:2323`.
+    pub fn bind<A: ToSocketAddrs>(address: A) -> Result<TelnetServer> {
+        match TcpListener::bind(address) {
+            Ok(listener) => Ok(TelnetServer { listener }),
+            Err(error) => Err(Error::io("Couldn't listen on the address.", error)),
+        }
+    }
+
+    pub fn get_local_address(&self) -> Result<SocketAddr> {
+        match self.listener.local_addr() {
+            Ok(address) => Ok(address),
+            Err(error) => Err(Error::io("Couldn't get the server address.", error)),
+        }
+    }
+
+    /// Waits for the next client and negotiates its session.
+    pub fn accept(&self) -> Result<TelnetApplication> {
+        match self.listener.accept() {
+            Ok((stream, _)) => TelnetApplication::new(stream),
+            Err(error) => Err(Error::io("Couldn't accept the connection.", error)),
+        }
+    }
+
+    /// Serves every client on its own thread, running `handler` with its
+    /// application. A failing session only closes its own connection.
+    pub fn serve<F>(self, handler: F) -> Result<()>
+    where
+        F: Fn(&mut TelnetApplication) -> Result<()> + Send + Sync + 'static,
+    {
+        let handler = Arc::new(handler);
+
+        loop {
+            let stream = match self.listener.accept() {
+                Ok((stream, _)) => stream,
+                Err(error) => return Err(Error::io("Couldn't accept the connection.", error)),
+            };
+            let handler = handler.clone();
+
+            thread::spawn(move || {
+                if let Ok(mut application) = TelnetApplication::new(stream) {
+                    let _ = handler(&mut application);
+                    let _ = application.disconnect();
+                }
+            });
+        }
+    }
+}
@@ -1,3 +1,4 @@
+extern crate libc;
 extern crate termion;
 extern crate tokterm_core;
 
@@ -1,39 +1,186 @@
 use color::color_to_u8;
-use std::io::{stdout, Stdout, Write};
+use libc::{tcgetattr, tcsetattr, termios, STDOUT_FILENO, TCSANOW};
+use std::io::{self, stdout, Stdout, Write};
+use std::mem::zeroed;
 use termion::color;
 use termion::cursor::{Goto, Hide, Show};
-use termion::input::{MouseTerminal};
 use termion::raw::{IntoRawMode, RawTerminal};
+use termion::screen::{ToAlternateScreen, ToMainScreen};
 use termion::terminal_size;
 use tokterm_core::drawing::cell_buffer::CellBuffer;
+use tokterm_core::drawing::palette::Palette;
 use tokterm_core::drawing::point_2d::Point2d;
+use tokterm_core::drawing::rgb::Rgb;
 use tokterm_core::drawing::size_2d::Size2d;
+use tokterm_core::system::capabilities::{Capabilities, ColorDepth};
+use tokterm_core::system::clipboard::{get_copy_sequence, CLIPBOARD_REQUEST};
+use tokterm_core::system::cursor::{
+    get_color_sequence, get_style_sequence, CursorStyle, CURSOR_RESET_SEQUENCE,
+};
+use tokterm_core::system::restore::{clear_restore_handler, set_restore_handler};
+use tokterm_core::system::signals::{enable_suspend_key, watch_suspend};
 use tokterm_core::system::terminal::Terminal;
+use tokterm_core::system::title::TitleStack;
+use tokterm_core::Error;
 use tokterm_core::Result;
 use termion::clear;
 use termion::async_stdin;
 use termion::AsyncReader;
 
+/// Resets the colors, disables the mouse and focus reports, bracketed paste
+/// and the kitty keyboard protocol, and shows the cursor.
+const RESET_SEQUENCE: &str = "\x1b[0m\x1b[?1006l\x1b[?1003l\x1b[?1004l\x1b[?2004l\x1b[<u\x1b[?25h";
+
 pub struct TermionTerminal {
-    stdout: MouseTerminal<RawTerminal<Stdout>>,
+    stdout: RawTerminal<Stdout>,
     stdin: AsyncReader,
+    palette: Option<Palette>,
+    capabilities: Capabilities,
+    alternate_screen: bool,
+    original: termios,
+    raw: termios,
+    cursor_style: Option<CursorStyle>,
+    cursor_color: Option<Rgb>,
+    titles: TitleStack,
 }
 
 impl TermionTerminal {
+    /// Creates a terminal over the standard output in raw mode, where the
+    /// suspend key still works. The terminal is restored when dropped, or
+    /// before the message of a panic is printed.
     pub fn create() -> Result<TermionTerminal> {
+        let original = get_attributes()?;
         let raw_terminal = match stdout().into_raw_mode() {
             Ok(raw_terminal) => raw_terminal,
-            Err(_) => return Err("Couldn't enter into raw mode."),
+            Err(error) => return Err(Error::io("Couldn't enter into raw mode.", error)),
         };
 
-        let stdout = MouseTerminal::from(raw_terminal);
+        let mut raw = get_attributes()?;
+        enable_suspend_key(&mut raw);
+        set_attributes(&raw)?;
+        watch_suspend()?;
+
+        set_restore_handler(Box::new(move || {
+            let mut output = stdout();
+            let _ = write!(
+                output,
+                "{}{}{}",
+                RESET_SEQUENCE, CURSOR_RESET_SEQUENCE, ToMainScreen
+            );
+            let _ = output.flush();
+            let _ = set_attributes(&original);
+        }));
+
         let stdin = async_stdin();
 
-        Ok(TermionTerminal { stdout, stdin })
+        Ok(TermionTerminal {
+            stdout: raw_terminal,
+            stdin,
+            palette: None,
+            capabilities: Capabilities::from_env(),
+            alternate_screen: false,
+            original,
+            raw,
+            cursor_style: None,
+            cursor_color: None,
+            titles: TitleStack::new(),
+        })
+    }
+
+    /// Switches to the alternate screen, which keeps the contents of the
+    /// main one until it is left, or switches back to the main screen.
+    pub fn set_alternate_screen(&mut self, enabled: bool) -> Result<()> {
+        let result = if enabled {
+            write!(self.stdout, "{}", ToAlternateScreen)
+        } else {
+            write!(self.stdout, "{}", ToMainScreen)
+        };
+
+        if let Err(error) = result.and_then(|_| self.stdout.flush()) {
+            return Err(Error::io("Couldn't switch the screen.", error));
+        }
+
+        self.alternate_screen = enabled;
+        Ok(())
     }
 
     #[inline]
-    pub fn get_stdout(&mut self) -> &mut MouseTerminal<RawTerminal<Stdout>> {
+    pub fn is_alternate_screen(&self) -> bool {
+        self.alternate_screen
+    }
+
+    /// Gives the terminal back to the shell before the process is stopped,
+    /// resetting it and leaving the alternate screen and the raw mode.
+    pub fn suspend(&mut self) -> Result<()> {
+        let screen = if self.alternate_screen {
+            format!("{}", ToMainScreen)
+        } else {
+            String::new()
+        };
+
+        self.write_sequence(&format!("{}{}", RESET_SEQUENCE, screen))?;
+        self.restore_cursor()?;
+        set_attributes(&self.original)
+    }
+
+    /// Takes the terminal again once the process is continued, entering the
+    /// raw mode and the alternate screen it had. The screen is cleared, so
+    /// everything has to be drawn again, and the reports have to be enabled
+    /// again.
+    pub fn resume(&mut self) -> Result<()> {
+        set_attributes(&self.raw)?;
+
+        let screen = if self.alternate_screen {
+            format!("{}", ToAlternateScreen)
+        } else {
+            String::new()
+        };
+
+        self.write_sequence(&format!("{}{}", screen, clear::All))?;
+        self.apply_cursor()
+    }
+
+    /// Restores the user's cursor, keeping the style and color to apply them
+    /// again when resumed.
+    fn restore_cursor(&mut self) -> Result<()> {
+        if self.cursor_style.is_some() {
+            self.write_sequence(&get_style_sequence(None))?;
+        }
+
+        if self.cursor_color.is_some() {
+            self.write_sequence(&get_color_sequence(None))?;
+        }
+
+        Ok(())
+    }
+
+    fn apply_cursor(&mut self) -> Result<()> {
+        if let Some(style) = self.cursor_style {
+            self.write_sequence(&get_style_sequence(Some(style)))?;
+        }
+
+        if let Some(color) = self.cursor_color {
+            self.write_sequence(&get_color_sequence(Some(color)))?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes an escape sequence termion has no type for, right away.
+    pub fn write_sequence(&mut self, sequence: &str) -> Result<()> {
+        if let Err(error) = self.stdout.write_all(sequence.as_bytes()) {
+            return Err(Error::io("Couldn't write to the terminal.", error));
+        }
+
+        if let Err(error) = self.stdout.flush() {
+            return Err(Error::io("Couldn't flush the buffer.", error));
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn get_stdout(&mut self) -> &mut RawTerminal<Stdout> {
         &mut self.stdout
     }
 
@@ -43,17 +190,34 @@ impl TermionTerminal {
     }
 }
 
+impl Drop for TermionTerminal {
+    fn drop(&mut self) {
+        let title = self.titles.restore();
+        let _ = write!(self.stdout, "{}{}", RESET_SEQUENCE, title);
+        let _ = self.restore_cursor();
+
+        if self.alternate_screen {
+            let _ = self.set_alternate_screen(false);
+        }
+
+        let _ = self.stdout.flush();
+
+        // the raw mode is left right after, when the field is dropped.
+        clear_restore_handler();
+    }
+}
+
 impl Terminal for TermionTerminal {
     /// Shows or hides the cursor.
     fn set_cursor_visibility(&mut self, visible: bool) -> Result<()> {
         if visible {
             match self.stdout.write(format!("{}", Show).as_bytes()) {
-                Err(_) => return Err("Couldn't show the cursor."),
+                Err(error) => return Err(Error::io("Couldn't show the cursor.", error)),
                 _ => ()
             };
         } else {
             match self.stdout.write(format!("{}", Hide).as_bytes())  {
-                Err(_) => return Err("Couldn't hide the cursor."),
+                Err(error) => return Err(Error::io("Couldn't hide the cursor.", error)),
                 _ => ()
             };
         }
@@ -64,17 +228,88 @@ impl Terminal for TermionTerminal {
     /// Moves the console cursor to a given position.
     fn set_cursor(&mut self, position: Point2d) -> Result<()> {
         match self.stdout.write(format!("{}", Goto(position.x as u16 + 1, position.y as u16 + 1)).as_bytes()) {
-            Err(_) => return Err("Couldn't set the cursor position."),
+            Err(error) => return Err(Error::io("Couldn't set the cursor position.", error)),
             _ => ()
         };
         Ok(())
     }
 
+    /// Sets the shape of the cursor and whether it blinks, or restores the
+    /// user's own cursor when `None` is given.
+    fn set_cursor_style(&mut self, style: Option<CursorStyle>) -> Result<()> {
+        self.write_sequence(&get_style_sequence(style))?;
+        self.cursor_style = style;
+        Ok(())
+    }
+
+    /// Sets the color of the cursor, or restores the user's own color when
+    /// `None` is given.
+    fn set_cursor_color(&mut self, color: Option<Rgb>) -> Result<()> {
+        self.write_sequence(&get_color_sequence(color))?;
+        self.cursor_color = color;
+        Ok(())
+    }
+
+    /// Sets the window title, which also labels the tab of the terminal.
+    /// The user's title is restored on exit.
+    fn set_title(&mut self, title: &str) -> Result<()> {
+        if !self.capabilities.title {
+            return Err(Error::NotSupported(
+                "The terminal can not change its title.",
+            ));
+        }
+
+        let sequence = self.titles.set_title(title);
+        self.write_sequence(&sequence)
+    }
+
+    /// Saves the current title, so it can be restored with `pop_title`.
+    fn push_title(&mut self) -> Result<()> {
+        if !self.capabilities.title {
+            return Err(Error::NotSupported(
+                "The terminal can not change its title.",
+            ));
+        }
+
+        let sequence = self.titles.push_title();
+        self.write_sequence(&sequence)
+    }
+
+    /// Restores the last title saved with `push_title`.
+    fn pop_title(&mut self) -> Result<()> {
+        let sequence = self.titles.pop_title()?;
+        self.write_sequence(&sequence)
+    }
+
+    /// Copies a text to the system clipboard.
+    fn copy_to_clipboard(&mut self, text: &str) -> Result<()> {
+        if !self.capabilities.clipboard {
+            return Err(Error::NotSupported(
+                "The terminal can not access the clipboard.",
+            ));
+        }
+
+        self.write_sequence(&get_copy_sequence(text))
+    }
+
+    /// Asks for the text of the system clipboard, which arrives later as an
+    /// `Event::Clipboard`. Terminals may ignore the request, for instance
+    /// when the user didn't allow reading the clipboard.
+    fn request_clipboard(&mut self) -> Result<()> {
+        if !self.capabilities.clipboard {
+            return Err(Error::NotSupported(
+                "The terminal can not access the clipboard.",
+            ));
+        }
+
+        self.write_sequence(CLIPBOARD_REQUEST)
+    }
+
     /// Gets the current console size in character units.
     fn get_console_size(&self) -> Result<Size2d> {
         let term_size = match terminal_size() {
             Ok(size) => size,
-            Err(_) => return Err("Couldn't retrieve"),
+            Err(error) => return Err(Error::io("Couldn't retrieve the terminal size.", error)),
         };
 
         Ok(Size2d {
@@ -86,7 +321,7 @@ impl Terminal for TermionTerminal {
     /// Clears the console screen.
     fn clear(&mut self) -> Result<()> {
         match self.stdout.write(format!("{}", clear::All).as_bytes()) {
-            Err(_) => return Err("Couldn't clear the screen."),
+            Err(error) => return Err(Error::io("Couldn't clear the screen.", error)),
             _ => ()
         };
         Ok(())
@@ -98,25 +333,89 @@ impl Terminal for TermionTerminal {
 
         let mut buffer = String::default();
 
+        // palettes need true color, otherwise the base colors are kept.
+        let palette = match self.capabilities.color_depth {
+            ColorDepth::TrueColor => self.palette.as_ref(),
+            _ => None,
+        };
+
         for cell in cell_buffer.iter() {
-            buffer += &format!(
-                "{}{}{}",
-                color::Bg(color::AnsiValue(color_to_u8(cell.background))),
-                color::Fg(color::AnsiValue(color_to_u8(cell.foreground))),
-                cell.character
-            );
+            buffer += &match palette {
+                Some(palette) => {
+                    let background = palette.get(cell.background);
+                    let foreground = palette.get(cell.foreground);
+                    format!(
+                        "{}{}{}",
+                        color::Bg(color::Rgb(background.red, background.green, background.blue)),
+                        color::Fg(color::Rgb(foreground.red, foreground.green, foreground.blue)),
+                        cell.character
+                    )
+                }
+                None => format!(
+                    "{}{}{}",
+                    color::Bg(color::AnsiValue(color_to_u8(cell.background))),
+                    color::Fg(color::AnsiValue(color_to_u8(cell.foreground))),
+                    cell.character
+                ),
+            };
         }
 
         match self.stdout.write(buffer.as_bytes()) {
-            Err(_) => return Err("Couldn't write to the terminal"),
+            Err(error) => return Err(Error::io("Couldn't write to the terminal", error)),
             _ => ()
         };
 
         match self.stdout.flush() {
-            Err(_) => return Err("Couldn't flush the buffer."),
+            Err(error) => return Err(Error::io("Couldn't flush the buffer.", error)),
             _ => ()
         };
 
         Ok(())
     }
+
+    /// Sets the palette used to present the base colors, or restores the
+    /// terminal's own colors when `None` is given.
+    fn set_palette(&mut self, palette: Option<&Palette>) -> Result<()> {
+        // palettes are drawn with true color, restoring the own colors always
+        // works.
+        if palette.is_some() && self.capabilities.color_depth != ColorDepth::TrueColor {
+            return Err(Error::NotSupported(
+                "The terminal can not change its colors.",
+            ));
+        }
+
+        self.palette = palette.cloned();
+        Ok(())
+    }
+
+    /// Gets what the terminal supports, detected from the environment unless
+    /// the backend knows better.
+    #[inline]
+    fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+}
+
+fn get_attributes() -> Result<termios> {
+    let mut attributes: termios = unsafe { zeroed() };
+
+    if unsafe { tcgetattr(STDOUT_FILENO, &mut attributes) } != 0 {
+        return Err(Error::io(
+            "Couldn't retrieve the terminal attributes.",
+            io::Error::last_os_error(),
+        ));
+    }
+
+    Ok(attributes)
+}
+
+fn set_attributes(attributes: &termios) -> Result<()> {
+    if unsafe { tcsetattr(STDOUT_FILENO, TCSANOW, attributes) } != 0 {
+        return Err(Error::io(
+            "Couldn't change the terminal attributes.",
+            io::Error::last_os_error(),
+        ));
+    }
+
+    Ok(())
 }
@@ -1,6 +1,6 @@
 use tokterm_core::drawing::point_2d::Point2d;
 use tokterm_core::events::event::{
-    Event, KeyboardEvent, KeyboardEventType, MouseEvent, MouseEventType, WindowEvent,
+    Event, KeyboardEvent, KeyboardEventType, MouseButton, MouseEvent, MouseEventType, WindowEvent,
     WindowEventType,
 };
 use tokterm_core::events::event_queue::EventQueue;
@@ -21,17 +21,19 @@ use winapi::um::wincon::LEFT_ALT_PRESSED;
 use winapi::um::wincon::LEFT_CTRL_PRESSED;
 use winapi::um::wincon::RIGHT_ALT_PRESSED;
 use winapi::um::wincon::RIGHT_CTRL_PRESSED;
+use winapi::um::wincon::SHIFT_PRESSED;
 use winapi::um::wincon::{
     FROM_LEFT_1ST_BUTTON_PRESSED, FROM_LEFT_2ND_BUTTON_PRESSED, FROM_LEFT_3RD_BUTTON_PRESSED,
     FROM_LEFT_4TH_BUTTON_PRESSED, DOUBLE_CLICK, ENABLE_EXTENDED_FLAGS, ENABLE_MOUSE_INPUT,
     ENABLE_QUICK_EDIT_MODE, ENABLE_WINDOW_INPUT, FOCUS_EVENT, INPUT_RECORD, KEY_EVENT, MOUSE_EVENT,
-    MOUSE_HWHEELED, MOUSE_MOVED, MOUSE_WHEELED, RIGHTMOST_BUTTON_PRESSED,
+    MOUSE_HWHEELED, MOUSE_WHEELED, RIGHTMOST_BUTTON_PRESSED,
 };
 
 use winapi::um::winuser::GetKeyState;
 use winapi::um::winuser::{VK_LSHIFT, VK_RSHIFT};
 use window::WindowsWindow;
 use Empty;
+use last_error;
 
 #[allow(dead_code)]
 pub struct WindowsApplication {
@@ -60,7 +62,7 @@ impl WindowsApplication {
             unsafe { GetConsoleMode(application.terminal.input_handle, &mut console_mode) };
 
         if success == -1 {
-            return Err("Couldn't retrieve the console mode.");
+            return Err(last_error("Couldn't retrieve the console mode."));
         }
 
         console_mode &= !ENABLE_QUICK_EDIT_MODE;
@@ -69,7 +71,7 @@ impl WindowsApplication {
         let success = unsafe { SetConsoleMode(application.terminal.input_handle, console_mode) };
 
         if success == -1 {
-            return Err("Couldn't set the console mode.");
+            return Err(last_error("Couldn't set the console mode."));
         }
 
         Ok(application)
@@ -125,7 +127,7 @@ impl Application for WindowsApplication {
             unsafe { GetNumberOfConsoleInputEvents(self.terminal.input_handle, &mut events_read) };
 
         if success == -1 {
-            return Err("Couldn't determine the amount of unread events");
+            return Err(last_error("Couldn't determine the amount of unread events"));
         }
 
         if events_read <= 0 {
@@ -142,24 +144,24 @@ impl Application for WindowsApplication {
         };
 
         if success == -1 {
-            return Err("Couldn't retrieve the console window events.");
+            return Err(last_error("Couldn't retrieve the console window events."));
         }
 
         for input_record in input_records.iter() {
             let event = match input_record.EventType {
                 KEY_EVENT => {
                     let event = process_key_event(input_record);
-                    self.event_queue.add_event(event);
+                    self.event_queue.add_event(event.clone());
                     event
                 }
                 MOUSE_EVENT => {
-                    let event = process_mouse_event(input_record);
-                    self.event_queue.add_event(event);
+                    let event = process_mouse_event(input_record, &self.mouse_state);
+                    self.event_queue.add_event(event.clone());
                     event
                 }
                 FOCUS_EVENT => {
-                    let event = process_window_event(&self.window)?;
-                    self.event_queue.add_event(event);
+                    let event = process_window_event(&self.window, input_record)?;
+                    self.event_queue.add_event(event.clone());
                     event
                 }
                 _ => continue,
@@ -168,7 +170,7 @@ impl Application for WindowsApplication {
             match event {
                 Event::Mouse(mouse) => self.mouse_state.update_from_event(mouse),
                 Event::Keyboard(keyboard) => self.keyboard_state.update_from_event(keyboard),
-                Event::Window(_) => (),
+                _ => (),
             }
         }
 
@@ -198,37 +200,78 @@ fn process_key_event(input_record: &INPUT_RECORD) -> Event {
     })
 }
 
+/// Converts a console mouse event. The console tells the buttons held, so
+/// the one pressed or released is found comparing them with the state.
 #[inline]
-fn process_mouse_event(input_record: &INPUT_RECORD) -> Event {
+fn process_mouse_event(input_record: &INPUT_RECORD, state: &MouseState) -> Event {
     let mouse_event = unsafe { input_record.Event.MouseEvent() };
+    let button_state = mouse_event.dwButtonState;
+    let control_state = mouse_event.dwControlKeyState;
+
+    let buttons = [
+        (MouseButton::Left, FROM_LEFT_1ST_BUTTON_PRESSED),
+        (MouseButton::Middle, FROM_LEFT_2ND_BUTTON_PRESSED),
+        (MouseButton::Right, RIGHTMOST_BUTTON_PRESSED),
+        (MouseButton::Extra1, FROM_LEFT_3RD_BUTTON_PRESSED),
+        (MouseButton::Extra2, FROM_LEFT_4TH_BUTTON_PRESSED),
+    ];
+    let changed = buttons
+        .iter()
+        .find(|&&(button, mask)| (button_state & mask != 0) != state.is_held(button));
+
+    let (event_type, button) = match mouse_event.dwEventFlags {
+        0 | DOUBLE_CLICK => match changed {
+            Some(&(button, mask)) if button_state & mask != 0 => {
+                if mouse_event.dwEventFlags == DOUBLE_CLICK {
+                    (MouseEventType::DoubleClick, button)
+                } else {
+                    (MouseEventType::MouseDown, button)
+                }
+            }
+            Some(&(button, _)) => (MouseEventType::MouseUp, button),
+            None => (MouseEventType::MouseMove, MouseButton::None),
+        },
+        MOUSE_WHEELED => (MouseEventType::Wheel, MouseButton::None),
+        MOUSE_HWHEELED => (MouseEventType::HorizontalWheel, MouseButton::None),
+        _ => (MouseEventType::MouseMove, MouseButton::None),
+    };
 
     Event::Mouse(MouseEvent {
-        event_type: match mouse_event.dwEventFlags {
-            0 => MouseEventType::Click,
-            MOUSE_MOVED => MouseEventType::MouseMove,
-            MOUSE_WHEELED | MOUSE_HWHEELED => MouseEventType::Wheel,
-            DOUBLE_CLICK => MouseEventType::DoubleClick,
-            _ => MouseEventType::MouseMove,
-        },
-        left_button: mouse_event.dwButtonState & FROM_LEFT_1ST_BUTTON_PRESSED != 0,
-        middle_button: mouse_event.dwButtonState & FROM_LEFT_2ND_BUTTON_PRESSED != 0,
-        right_button: mouse_event.dwButtonState & RIGHTMOST_BUTTON_PRESSED != 0,
-        extra_button_1: mouse_event.dwButtonState & FROM_LEFT_3RD_BUTTON_PRESSED != 0,
-        extra_button_2: mouse_event.dwButtonState & FROM_LEFT_4TH_BUTTON_PRESSED != 0,
+        event_type,
+        button,
+        left_button: button_state & FROM_LEFT_1ST_BUTTON_PRESSED != 0,
+        middle_button: button_state & FROM_LEFT_2ND_BUTTON_PRESSED != 0,
+        right_button: button_state & RIGHTMOST_BUTTON_PRESSED != 0,
+        extra_button_1: button_state & FROM_LEFT_3RD_BUTTON_PRESSED != 0,
+        extra_button_2: button_state & FROM_LEFT_4TH_BUTTON_PRESSED != 0,
         extra_button_3: false,
         extra_button_4: false,
-        wheel_delta: get_wheel_delta(mouse_event.dwButtonState),
+        wheel_delta: match event_type {
+            MouseEventType::Wheel | MouseEventType::HorizontalWheel => {
+                get_wheel_delta(button_state)
+            }
+            _ => 0,
+        },
         position: Point2d::new(
             mouse_event.dwMousePosition.X as i32,
             mouse_event.dwMousePosition.Y as i32,
         ),
+        control: control_state & (LEFT_CTRL_PRESSED | RIGHT_CTRL_PRESSED) != 0,
+        shift: control_state & SHIFT_PRESSED != 0,
+        menu: control_state & (LEFT_ALT_PRESSED | RIGHT_ALT_PRESSED) != 0,
     })
 }
 
 #[inline]
-fn process_window_event(window: &WindowsWindow) -> Result<Event> {
+fn process_window_event(window: &WindowsWindow, input_record: &INPUT_RECORD) -> Result<Event> {
+    let focus_event = unsafe { input_record.Event.FocusEvent() };
+
     Ok(Event::Window(WindowEvent {
-        event_type: WindowEventType::WindowFocus,
+        event_type: if focus_event.bSetFocus != 0 {
+            WindowEventType::WindowFocus
+        } else {
+            WindowEventType::WindowLostFocus
+        },
         position: window.get_window_position()?,
         size: window.get_window_size()?,
     }))
@@ -1,6 +1,8 @@
 extern crate tokterm_core;
 extern crate winapi;
+use std::io;
 use std::mem::zeroed;
+use tokterm_core::Error;
 use winapi::shared::windef::{POINT, RECT};
 use winapi::um::wincon::CHAR_INFO;
 use winapi::um::wincon::INPUT_RECORD;
@@ -91,3 +93,9 @@ pub fn get_wstring(msg: &str) -> Vec<u16> {
 
     OsStr::new(msg).encode_wide().chain(once(0)).collect()
 }
+
+/// Wraps the last Windows error code into an `Error`.
+#[inline]
+pub fn last_error(context: &'static str) -> Error {
+    Error::io(context, io::Error::last_os_error())
+}
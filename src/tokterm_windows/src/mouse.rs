@@ -7,6 +7,7 @@ use winapi::um::winuser::{
     GetCursorPos, LoadCursorW, ScreenToClient, SetCursor, SetCursorPos, IDC_ARROW,
 };
 use Empty;
+use last_error;
 
 #[derive(Debug)]
 pub struct WindowsMouse {
@@ -25,7 +26,7 @@ impl WindowsMouse {
         let success = unsafe { GetCursorPos(&mut point) };
 
         if success == 0 {
-            return Err("Problems trying to obtain the cursor position.");
+            return Err(last_error("Problems trying to obtain the cursor position."));
         }
 
         Ok(Point2d::new(point.x as i32, point.y as i32))
@@ -41,7 +42,7 @@ impl WindowsMouse {
         let success = unsafe { ScreenToClient(self.window_handle, &mut point) };
 
         if success == 0 {
-            return Err("Problems trying to obtain the client cursor position.");
+            return Err(last_error("Problems trying to obtain the client cursor position."));
         }
 
         Ok(Point2d::new(point.x as i32, point.y as i32))
@@ -51,7 +52,7 @@ impl WindowsMouse {
         let success = unsafe { SetCursorPos(position.x as i32, position.y as i32) };
 
         if success == 0 {
-            return Err("Problems trying to set the cursor position.");
+            return Err(last_error("Problems trying to set the cursor position."));
         }
 
         Ok(())
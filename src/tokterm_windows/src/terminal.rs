@@ -1,12 +1,19 @@
+use std::mem::{size_of, zeroed};
 use std::ptr::null_mut;
 use tokterm_core::drawing::cell::Cell;
 use tokterm_core::drawing::cell_buffer::CellBuffer;
+use tokterm_core::drawing::color::Color;
+use tokterm_core::drawing::palette::Palette;
 use tokterm_core::drawing::point_2d::Point2d;
 use tokterm_core::drawing::size_2d::Size2d;
+use tokterm_core::system::capabilities::{Capabilities, ColorDepth, MouseSupport};
+use tokterm_core::system::cursor::{CursorShape, CursorStyle};
 use tokterm_core::system::terminal::Terminal;
+use tokterm_core::Error;
 use tokterm_core::Result;
 use color::get_u16_from_color;
 use winapi::ctypes::c_void;
+use winapi::shared::windef::COLORREF;
 use winapi::shared::windef::HWND;
 use winapi::um::fileapi::CreateFileW;
 use winapi::um::handleapi::CloseHandle;
@@ -14,12 +21,14 @@ use winapi::um::processenv::GetStdHandle;
 use winapi::um::winbase::STD_INPUT_HANDLE;
 use winapi::um::winbase::STD_OUTPUT_HANDLE;
 use winapi::um::wincon::{
-    GetConsoleCursorInfo, GetConsoleScreenBufferInfo, GetConsoleWindow, SetConsoleCursorInfo,
-    SetConsoleCursorPosition, WriteConsoleOutputW, CHAR_INFO, CONSOLE_CURSOR_INFO,
-    CONSOLE_SCREEN_BUFFER_INFO, COORD, SMALL_RECT,
+    GetConsoleCursorInfo, GetConsoleScreenBufferInfo, GetConsoleScreenBufferInfoEx,
+    GetConsoleTitleW, GetConsoleWindow, SetConsoleCursorInfo, SetConsoleCursorPosition,
+    SetConsoleScreenBufferInfoEx, SetConsoleTitleW, WriteConsoleOutputW, CHAR_INFO,
+    CONSOLE_CURSOR_INFO, CONSOLE_SCREEN_BUFFER_INFO, CONSOLE_SCREEN_BUFFER_INFOEX, COORD,
+    SMALL_RECT,
 };
 use winapi::um::winnt::HANDLE;
-use {get_wstring, Empty};
+use {get_wstring, last_error, Empty};
 
 #[derive(Debug)]
 pub struct WindowsTerminal {
@@ -27,10 +36,26 @@ pub struct WindowsTerminal {
     pub output_handle: HANDLE,
     pub input_handle: HANDLE,
     pub window_handle: HWND,
+    original_color_table: Option<[COLORREF; 16]>,
+    original_cursor_size: Option<u32>,
+    original_title: Option<Vec<u16>>,
+    titles: Vec<Vec<u16>>,
 }
 
 impl Drop for WindowsTerminal {
     fn drop(&mut self) {
+        if let Some(color_table) = self.original_color_table {
+            let _ = self.set_color_table(color_table);
+        }
+
+        if self.original_cursor_size.is_some() {
+            let _ = self.set_cursor_style(None);
+        }
+
+        if let Some(title) = self.original_title.take() {
+            let _ = self.set_console_title(&title);
+        }
+
         unsafe { CloseHandle(self.console_handle) };
     }
 }
@@ -50,25 +75,25 @@ impl WindowsTerminal {
         };
 
         if console_handle == null_mut() {
-            return Err("Couldn't open the console output file.");
+            return Err(last_error("Couldn't open the console output file."));
         }
 
         let output_handle = unsafe { GetStdHandle(STD_OUTPUT_HANDLE) };
 
         if output_handle == null_mut() {
-            return Err("Couldn't retrieve the output handle.");
+            return Err(last_error("Couldn't retrieve the output handle."));
         }
 
         let input_handle = unsafe { GetStdHandle(STD_INPUT_HANDLE) };
 
         if output_handle == null_mut() {
-            return Err("Couldn't retrieve the input handle.");
+            return Err(last_error("Couldn't retrieve the input handle."));
         }
 
         let window_handle = unsafe { GetConsoleWindow() };
 
         if window_handle == null_mut() {
-            return Err("Couldn't retrieve the window handle.");
+            return Err(last_error("Couldn't retrieve the window handle."));
         }
 
         Ok(WindowsTerminal {
@@ -76,8 +101,75 @@ impl WindowsTerminal {
             output_handle,
             input_handle,
             window_handle,
+            original_color_table: None,
+            original_cursor_size: None,
+            original_title: None,
+            titles: Vec::new(),
         })
     }
+
+    /// Gets the console title as a NUL terminated wide string.
+    fn get_console_title(&self) -> Vec<u16> {
+        let mut title = vec![0u16; 1024];
+        let length = unsafe { GetConsoleTitleW(title.as_mut_ptr(), title.len() as u32) };
+
+        // a zero length is also returned for an empty title.
+        title.truncate(length as usize);
+        title.push(0);
+        title
+    }
+
+    fn set_console_title(&mut self, title: &[u16]) -> Result<()> {
+        if self.original_title.is_none() {
+            self.original_title = Some(self.get_console_title());
+        }
+
+        let success = unsafe { SetConsoleTitleW(title.as_ptr()) };
+
+        if success == 0 {
+            return Err(last_error("Problems trying to set the console title."));
+        }
+
+        Ok(())
+    }
+
+    fn get_color_table(&self) -> Result<[COLORREF; 16]> {
+        let mut info: CONSOLE_SCREEN_BUFFER_INFOEX = unsafe { zeroed() };
+        info.cbSize = size_of::<CONSOLE_SCREEN_BUFFER_INFOEX>() as u32;
+
+        let success = unsafe { GetConsoleScreenBufferInfoEx(self.output_handle, &mut info) };
+
+        if success == 0 {
+            return Err(last_error("Problems trying to obtain the console color table."));
+        }
+
+        Ok(info.ColorTable)
+    }
+
+    fn set_color_table(&mut self, color_table: [COLORREF; 16]) -> Result<()> {
+        let mut info: CONSOLE_SCREEN_BUFFER_INFOEX = unsafe { zeroed() };
+        info.cbSize = size_of::<CONSOLE_SCREEN_BUFFER_INFOEX>() as u32;
+
+        let success = unsafe { GetConsoleScreenBufferInfoEx(self.output_handle, &mut info) };
+
+        if success == 0 {
+            return Err(last_error("Problems trying to obtain the console color table."));
+        }
+
+        // the window rect is reported inclusive but set exclusive, so without
+        // this the console shrinks one cell on each call.
+        info.srWindow.Right += 1;
+        info.srWindow.Bottom += 1;
+        info.ColorTable = color_table;
+
+        let success = unsafe { SetConsoleScreenBufferInfoEx(self.output_handle, &mut info) };
+
+        if success == 0 {
+            return Err(last_error("Problems trying to set the console color table."));
+        }
+
+        Ok(())
+    }
 }
 
 #[allow(dead_code)]
@@ -87,7 +179,7 @@ impl Terminal for WindowsTerminal {
         let success = unsafe { GetConsoleCursorInfo(self.output_handle, &mut console_cursor_info) };
 
         if success == 0 {
-            return Err("Problems trying to obtain the console cursor info.");
+            return Err(last_error("Problems trying to obtain the console cursor info."));
         }
 
         console_cursor_info.bVisible = if visible { 1 } else { 0 };
@@ -95,12 +187,70 @@ impl Terminal for WindowsTerminal {
         let success = unsafe { SetConsoleCursorInfo(self.output_handle, &mut console_cursor_info) };
 
         if success == 0 {
-            return Err("Problems trying to set the console cursor info.");
+            return Err(last_error("Problems trying to set the console cursor info."));
         }
 
         Ok(())
     }
 
+    /// Sets the shape of the cursor and whether it blinks, or restores the
+    /// user's own cursor when `None` is given.
+    fn set_cursor_style(&mut self, style: Option<CursorStyle>) -> Result<()> {
+        let mut console_cursor_info = CONSOLE_CURSOR_INFO::empty();
+        let success = unsafe { GetConsoleCursorInfo(self.output_handle, &mut console_cursor_info) };
+
+        if success == 0 {
+            return Err(last_error("Problems trying to obtain the console cursor info."));
+        }
+
+        if self.original_cursor_size.is_none() {
+            self.original_cursor_size = Some(console_cursor_info.dwSize);
+        }
+
+        // the console only changes the percentage of the cell filled by the
+        // cursor, which always blinks.
+        console_cursor_info.dwSize = match style {
+            Some(style) => match style.shape {
+                CursorShape::Block => 100,
+                CursorShape::Underline => 25,
+                CursorShape::Bar => 10,
+            },
+            None => match self.original_cursor_size {
+                Some(size) => size,
+                None => console_cursor_info.dwSize,
+            },
+        };
+
+        let success = unsafe { SetConsoleCursorInfo(self.output_handle, &mut console_cursor_info) };
+
+        if success == 0 {
+            return Err(last_error("Problems trying to set the console cursor info."));
+        }
+
+        Ok(())
+    }
+
+    /// Sets the window title, which also labels the tab of the terminal.
+    /// The user's title is restored on exit.
+    fn set_title(&mut self, title: &str) -> Result<()> {
+        self.set_console_title(&get_wstring(title))
+    }
+
+    /// Saves the current title, so it can be restored with `pop_title`.
+    fn push_title(&mut self) -> Result<()> {
+        let title = self.get_console_title();
+        self.titles.push(title);
+        Ok(())
+    }
+
+    /// Restores the last title saved with `push_title`.
+    fn pop_title(&mut self) -> Result<()> {
+        match self.titles.pop() {
+            Some(title) => self.set_console_title(&title),
+            None => Err(Error::InvalidOperation("There is no title to pop.")),
+        }
+    }
+
     fn set_cursor(&mut self, position: Point2d) -> Result<()> {
         let success: i32 = unsafe {
             SetConsoleCursorPosition(
@@ -113,7 +263,7 @@ impl Terminal for WindowsTerminal {
         };
 
         if success == 0 {
-            return Err("Couldn't set the console cursor position.");
+            return Err(last_error("Couldn't set the console cursor position."));
         }
 
         Ok(())
@@ -126,7 +276,7 @@ impl Terminal for WindowsTerminal {
         };
 
         if success == 0 {
-            return Err("Problems trying to obtain the screen buffer info.");
+            return Err(last_error("Problems trying to obtain the screen buffer info."));
         }
 
         let window = console_screen_buffer_info.srWindow;
@@ -170,7 +320,7 @@ impl Terminal for WindowsTerminal {
         };
 
         if success == 0 {
-            return Err("Couldn't clear console output.");
+            return Err(last_error("Couldn't clear console output."));
         }
 
         Ok(())
@@ -214,9 +364,49 @@ impl Terminal for WindowsTerminal {
         };
 
         if success == 0 {
-            return Err("Couldn't write to the console output.");
+            return Err(last_error("Couldn't write to the console output."));
         }
 
         Ok(())
     }
+
+    fn set_palette(&mut self, palette: Option<&Palette>) -> Result<()> {
+        let palette = match palette {
+            Some(palette) => palette,
+            None => {
+                return match self.original_color_table.take() {
+                    Some(color_table) => self.set_color_table(color_table),
+                    None => Ok(()),
+                };
+            }
+        };
+
+        if self.original_color_table.is_none() {
+            self.original_color_table = Some(self.get_color_table()?);
+        }
+
+        let mut color_table: [COLORREF; 16] = [0; 16];
+
+        for color in Color::to_vec() {
+            let rgb = palette.get(color);
+            color_table[get_u16_from_color(color) as usize] =
+                (rgb.blue as u32) << 16 | (rgb.green as u32) << 8 | rgb.red as u32;
+        }
+
+        self.set_color_table(color_table)
+    }
+
+    /// Gets what the terminal supports, detected from the environment unless
+    /// the backend knows better.
+    fn capabilities(&self) -> Capabilities {
+        // the console API reports the mouse itself and always redefines its
+        // 16 colors, there are no terminfo entries to look at.
+        Capabilities {
+            color_depth: ColorDepth::Ansi16,
+            mouse: MouseSupport::Normal,
+            title: true,
+            change_palette: true,
+            ..Capabilities::basic()
+        }
+    }
 }
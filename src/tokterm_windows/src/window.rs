@@ -5,6 +5,7 @@ use winapi::shared::windef::{HWND, RECT};
 use winapi::um::wincon::GetConsoleWindow;
 use winapi::um::winuser::{GetClientRect, GetWindowRect, SetWindowPos};
 use Empty;
+use last_error;
 
 #[derive(Debug)]
 pub struct WindowsWindow {
@@ -23,7 +24,7 @@ impl WindowsWindow {
         let success = unsafe { GetWindowRect(self.window_handle, &mut rect) };
 
         if success == 0 {
-            return Err("Problems trying to obtain the window rect.");
+            return Err(last_error("Problems trying to obtain the window rect."));
         }
 
         Ok(Size2d::new(
@@ -37,7 +38,7 @@ impl WindowsWindow {
         let success = unsafe { GetClientRect(self.window_handle, &mut rect) };
 
         if success == 0 {
-            return Err("Problems trying to obtain the client rect.");
+            return Err(last_error("Problems trying to obtain the client rect."));
         }
 
         Ok(Size2d::new(
@@ -51,7 +52,7 @@ impl WindowsWindow {
         let success = unsafe { GetWindowRect(self.window_handle, &mut rect) };
 
         if success == 0 {
-            return Err("Problems trying to obtain the window rect.");
+            return Err(last_error("Problems trying to obtain the window rect."));
         }
 
         let success = unsafe {
@@ -67,7 +68,7 @@ impl WindowsWindow {
         };
 
         if success == 0 {
-            return Err("Problem trying to set the windows size.");
+            return Err(last_error("Problem trying to set the windows size."));
         }
 
         Ok(())
@@ -78,7 +79,7 @@ impl WindowsWindow {
         let success = unsafe { GetWindowRect(self.window_handle, &mut rect) };
 
         if success == 0 {
-            return Err("Problems trying to obtain the window rect.");
+            return Err(last_error("Problems trying to obtain the window rect."));
         }
 
         Ok(Point2d::new(rect.left as i32, rect.top as i32))
@@ -89,7 +90,7 @@ impl WindowsWindow {
         let success = unsafe { GetWindowRect(self.window_handle, &mut rect) };
 
         if success == 0 {
-            return Err("Problems trying to obtain the window rect.");
+            return Err(last_error("Problems trying to obtain the window rect."));
         }
 
         let success = unsafe {
@@ -105,7 +106,7 @@ impl WindowsWindow {
         };
 
         if success == 0 {
-            return Err("Problem trying to set the windows position.");
+            return Err(last_error("Problem trying to set the windows position."));
         }
 
         Ok(())